@@ -1,5 +1,7 @@
 use clap::Parser;
 
+use crate::eval;
+
 #[derive(Parser, Debug)]
 #[clap(name = "inty", version, author, about, long_about = None)]
 pub struct Args {
@@ -7,10 +9,74 @@ pub struct Args {
     #[clap(long, short, action)]
     pub debug: bool,
 
+    /// When to color error output
+    #[clap(long, value_enum, default_value_t = Color::Auto, global = true)]
+    pub color: Color,
+
+    /// How non-boolean values are treated in conditions and logic
+    #[clap(long, value_enum, default_value_t = Truthiness::Legacy, global = true)]
+    pub truthiness: Truthiness,
+
+    /// Require booleans in conditions and logic (same as `--truthiness strict`)
+    #[clap(long, action, global = true, conflicts_with = "truthiness")]
+    pub strict: bool,
+
     #[clap(subcommand)]
     pub command: Command,
 }
 
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    /// Color output when stderr is a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+impl Color {
+    /// Check if error output, which is written to stderr, should be colored
+    pub fn enabled(&self) -> bool {
+        match self {
+            Color::Auto => std::io::IsTerminal::is_terminal(&std::io::stderr()),
+            Color::Always => true,
+            Color::Never => false,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum Truthiness {
+    /// Integers are true only when positive
+    Legacy,
+
+    /// Only booleans are allowed
+    Strict,
+
+    /// Numbers are true when non-zero
+    Conventional,
+}
+
+impl From<Truthiness> for eval::Truthiness {
+    fn from(value: Truthiness) -> Self {
+        match value {
+            Truthiness::Legacy => eval::Truthiness::Legacy,
+            Truthiness::Strict => eval::Truthiness::Strict,
+            Truthiness::Conventional => eval::Truthiness::Conventional,
+        }
+    }
+}
+
+impl Args {
+    /// Get the truthiness mode, taking `--strict` into account
+    pub fn truthiness(&self) -> eval::Truthiness {
+        if self.strict {
+            eval::Truthiness::Strict
+        } else {
+            self.truthiness.into()
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 pub enum Command {
     /// Run source code from a file
@@ -5,37 +5,87 @@ pub type IntyResult<T> = Result<T, IntyError>;
 #[derive(thiserror::Error, Debug)]
 pub enum IntyError {
     #[error("syntax error: {message}")]
-    SyntaxError { message: String },
+    SyntaxError { message: String, span: Span },
 
     #[error("syntax error: {message} {token}")]
-    SyntaxTokenError { token: Token, message: String },
+    SyntaxTokenError {
+        token: Token,
+        message: String,
+        span: Span,
+    },
 
     #[error("unexpected character: {character}")]
-    UnexpectedChar { character: char },
+    UnexpectedChar { character: char, span: Span },
 
-    #[error("could not parse token {character}: {message}")]
-    TokenParsingError { character: char, message: String },
+    #[error("could not parse token {text}: {message}")]
+    TokenParsingError {
+        text: String,
+        message: String,
+        span: Span,
+    },
 
     #[error("expected {expected}, found {found}")]
-    ExpectedTokenError { expected: Token, found: Token },
+    ExpectedTokenError {
+        expected: Token,
+        found: Token,
+        span: Span,
+    },
+
+    #[error("expected `{expected}` to close `{open}`")]
+    UnclosedDelimiterError {
+        open: char,
+        expected: char,
+        open_span: Span,
+        span: Span,
+    },
 
     #[error("logic error: {message}")]
-    LogicError { message: String },
+    LogicError { message: String, span: Span },
+
+    #[error("overflow: `{operation}` is too large")]
+    OverflowError { operation: String, span: Span },
 
     #[error("cannot divide by zero")]
-    DivideByZeroError,
+    DivideByZeroError { span: Span },
 
     #[error("invalid expression: {message}")]
-    InvalidExpressionError { message: String },
+    InvalidExpressionError { message: String, span: Span },
 
     #[error("unknown identifier: {ident}")]
-    UnknownIdentifier { ident: String },
+    UnknownIdentifier { ident: String, span: Span },
 
     #[error("type error: {message}")]
-    TypeError { message: String },
-
-    #[error("parse int error: {0}")]
-    ParseIntError(#[from] std::num::ParseIntError),
+    TypeError { message: String, span: Span },
+
+    #[error("index out of bounds: the length is {length} but the index is {index}")]
+    IndexOutOfBounds { index: Int, length: Int, span: Span },
+
+    #[error("pattern mismatch: {message}")]
+    PatternMismatch { message: String, span: Span },
+
+    #[error("non-exhaustive match on {ty}: missing {}", missing.join(", "))]
+    NonExhaustiveMatch {
+        ty: String,
+        missing: Vec<String>,
+        span: Span,
+    },
+
+    #[error("unknown field `{field}` on {ty}")]
+    UnknownField {
+        field: String,
+        ty: String,
+        span: Span,
+    },
+
+    #[error("key not found: {key}")]
+    KeyNotFound { key: String, span: Span },
+
+    #[error("expected {expected} arguments, found {found}")]
+    ArityError {
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
 
     #[error("i/o error: {0}")]
     IOError(#[from] std::io::Error),
@@ -49,3 +99,33 @@ pub enum IntyError {
     #[error("borrow mut error: {0}")]
     BorrowMutError(#[from] std::cell::BorrowMutError),
 }
+
+impl IntyError {
+    /// Get the location in the source code that caused the error, if there is one
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            IntyError::SyntaxError { span, .. }
+            | IntyError::SyntaxTokenError { span, .. }
+            | IntyError::UnexpectedChar { span, .. }
+            | IntyError::TokenParsingError { span, .. }
+            | IntyError::ExpectedTokenError { span, .. }
+            | IntyError::UnclosedDelimiterError { span, .. }
+            | IntyError::LogicError { span, .. }
+            | IntyError::OverflowError { span, .. }
+            | IntyError::DivideByZeroError { span }
+            | IntyError::InvalidExpressionError { span, .. }
+            | IntyError::UnknownIdentifier { span, .. }
+            | IntyError::TypeError { span, .. }
+            | IntyError::IndexOutOfBounds { span, .. }
+            | IntyError::KeyNotFound { span, .. }
+            | IntyError::UnknownField { span, .. }
+            | IntyError::PatternMismatch { span, .. }
+            | IntyError::NonExhaustiveMatch { span, .. }
+            | IntyError::ArityError { span, .. } => Some(*span),
+            IntyError::IOError(_)
+            | IntyError::ReadlineError(_)
+            | IntyError::BorrowError(_)
+            | IntyError::BorrowMutError(_) => None,
+        }
+    }
+}
@@ -3,10 +3,26 @@ use std::rc::Rc;
 use super::*;
 
 /// An expression is a group of child expressions that evaluate to a single value
+#[derive(Debug)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+/// The different kinds of expressions
 #[derive(Debug, PartialEq)]
-pub enum Expr {
+pub enum ExprKind {
     /// Single integer value (e.g. 42)
-    Integer(i32),
+    Integer(Int),
+
+    /// Single float value (e.g. 3.14)
+    Float(f64),
+
+    /// String value (e.g. "hello")
+    Str(String),
+
+    /// String with embedded expressions (e.g. "total: {x + 1}")
+    Interpolate(Vec<InterpolatePart>),
 
     /// Boolean value (e.g. true/false)
     Bool(bool),
@@ -14,9 +30,77 @@ pub enum Expr {
     /// Boolean value (e.g. true/false)
     List(Vec<Expr>),
 
+    /// The elements of a list or range, spread into a list literal (e.g. `[0, ...1..3]`)
+    Spread(Rc<Expr>),
+
+    /// Map of keys to values, in insertion order (e.g. `{ "a": 1, 2: true }`, `{:}`)
+    Map(Vec<(Expr, Expr)>),
+
+    /// Fixed-size group of values (e.g. `(1, true)`, `(1,)`)
+    Tuple(Vec<Expr>),
+
+    /// Record of a declared struct type, where the fields not given are copied from a base
+    /// record (e.g. `Point { x: 1, y: 2 }`, `Point { x: 1, ..p }`)
+    Record {
+        ident: String,
+        fields: Vec<(String, Expr)>,
+        base: Option<Rc<Expr>>,
+    },
+
+    /// Access to a field of a record (e.g. `p.x`)
+    Field { target: Rc<Expr>, field: String },
+
     /// A variable (e.g. `x`)
     Ident(String),
 
+    /// An if expression (e.g. `if <expr> <stmt> [<else> <stmt>]`)
+    If {
+        test: Rc<Expr>,
+        branch: Rc<Stmt>,
+        else_branch: Option<Rc<Stmt>>,
+    },
+
+    /// Evaluates the first arm whose pattern matches the value and whose guard holds
+    /// (e.g. `match s { Circle(r) if r > 0 => r, _ => 0 }`)
+    Match {
+        value: Rc<Expr>,
+        arms: Vec<MatchArm>,
+    },
+
+    /// A group of statements evaluating to the last one (e.g. `{ let x = 1; x + 2 }`)
+    Block(Vec<Stmt>),
+
+    /// An anonymous function (e.g. `fn(x) x * 2`)
+    Lambda { params: Vec<String>, body: Rc<Stmt> },
+
+    /// A range of integers, optionally including the end (e.g. `0..10`, `10..=0 step -2`)
+    Range {
+        start: Rc<Expr>,
+        end: Rc<Expr>,
+        step: Option<Rc<Expr>>,
+        inclusive: bool,
+    },
+
+    /// A function call (e.g. `foo(1, 2)`)
+    Call { callee: Rc<Expr>, args: Vec<Expr> },
+
+    /// Access to a single element, counting from the end if negative (e.g. `xs[0]`, `xs[-1]`)
+    Index { target: Rc<Expr>, index: Rc<Expr> },
+
+    /// A range of elements, where either bound may be left out (e.g. `xs[1:3]`, `xs[:-1]`)
+    Slice {
+        target: Rc<Expr>,
+        start: Option<Rc<Expr>>,
+        end: Option<Rc<Expr>>,
+    },
+
+    /// Increment or decrement of a variable (e.g. `++x`, `x--`)
+    Update {
+        operator: UpdOp,
+        ident: String,
+        prefix: bool,
+    },
+
     /// Unary operation (e.g. +1, -2)
     Unary { operator: UnOp, value: Rc<Expr> },
 
@@ -27,6 +111,12 @@ pub enum Expr {
         rhs: Rc<Expr>,
     },
 
+    Bitwise {
+        operator: BitOp,
+        lhs: Rc<Expr>,
+        rhs: Rc<Expr>,
+    },
+
     Logical {
         operator: LogOp,
         lhs: Rc<Expr>,
@@ -39,3 +129,31 @@ pub enum Expr {
         rhs: Rc<Expr>,
     },
 }
+
+/// A single arm of a match expression (e.g. `Circle(r) if r > 0 => r`)
+#[derive(Debug, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Stmt,
+}
+
+/// A piece of an interpolated string
+#[derive(Debug, PartialEq)]
+pub enum InterpolatePart {
+    Literal(String),
+    Expr(Expr),
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+// Expressions are compared by structure alone, regardless of where they are in the source
+impl PartialEq for Expr {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    ops::{Add, BitAnd, BitOr, BitXor, Mul, Neg, Not, Sub},
+    str::FromStr,
+};
+
+use num_bigint::{BigInt, ParseBigIntError};
+use num_traits::{FromPrimitive, Signed, ToPrimitive};
+
+/// Largest result `Int::checked_pow` will compute, in bits (roughly 1.2 million decimal digits)
+const MAX_POW_BITS: u64 = 1 << 22;
+
+/// An integer of any size. Values that fit in an `i64` are stored inline so ordinary
+/// arithmetic stays fast, and only spill over into a heap allocated `BigInt` when needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Int {
+    Small(i64),
+
+    /// Always outside of the `i64` range, so every value has exactly one representation
+    Big(BigInt),
+}
+
+impl Int {
+    /// Convert to a `BigInt`, regardless of size
+    pub fn to_big(&self) -> BigInt {
+        match self {
+            Int::Small(val) => BigInt::from(*val),
+            Int::Big(val) => val.clone(),
+        }
+    }
+
+    /// Convert a float to an integer, if it is finite and has no fractional part
+    pub fn from_f64(val: f64) -> Option<Self> {
+        match val.fract() == 0.0 {
+            true => BigInt::from_f64(val).map(Int::from),
+            false => None,
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Int::Small(val) => *val as f64,
+            Int::Big(val) => val.to_f64().unwrap_or(match val.is_negative() {
+                true => f64::NEG_INFINITY,
+                false => f64::INFINITY,
+            }),
+        }
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            Int::Small(val) => Some(*val),
+            Int::Big(_) => None,
+        }
+    }
+
+    pub fn to_usize(&self) -> Option<usize> {
+        match self {
+            Int::Small(val) => usize::try_from(*val).ok(),
+            Int::Big(_) => None,
+        }
+    }
+
+    pub fn to_u32(&self) -> Option<u32> {
+        match self {
+            Int::Small(val) => u32::try_from(*val).ok(),
+            Int::Big(_) => None,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        matches!(self, Int::Small(0))
+    }
+
+    pub fn is_positive(&self) -> bool {
+        match self {
+            Int::Small(val) => *val > 0,
+            Int::Big(val) => val.is_positive(),
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Int::Small(val) => *val < 0,
+            Int::Big(val) => val.is_negative(),
+        }
+    }
+
+    /// Divide, truncating towards zero, or `None` if dividing by zero
+    pub fn checked_div(&self, rhs: &Int) -> Option<Int> {
+        if rhs.is_zero() {
+            return None;
+        }
+
+        if let (Int::Small(lhs), Int::Small(rhs)) = (self, rhs) {
+            if let Some(val) = lhs.checked_div(*rhs) {
+                return Some(Int::Small(val));
+            }
+        }
+
+        Some(Int::from(self.to_big() / rhs.to_big()))
+    }
+
+    /// Remainder of truncating division, taking the sign of `self`, or `None` if dividing by zero
+    pub fn checked_rem(&self, rhs: &Int) -> Option<Int> {
+        if rhs.is_zero() {
+            return None;
+        }
+
+        if let (Int::Small(lhs), Int::Small(rhs)) = (self, rhs) {
+            if let Some(val) = lhs.checked_rem(*rhs) {
+                return Some(Int::Small(val));
+            }
+        }
+
+        Some(Int::from(self.to_big() % rhs.to_big()))
+    }
+
+    /// Divide, rounding towards negative infinity, or `None` if dividing by zero
+    pub fn checked_div_floor(&self, rhs: &Int) -> Option<Int> {
+        let quotient = self.checked_div(rhs)?;
+        let rem = self.checked_rem(rhs)?;
+
+        // Truncation rounded towards zero, which is upwards if the signs differ
+        Some(
+            match !rem.is_zero() && rem.is_negative() != rhs.is_negative() {
+                true => quotient - Int::Small(1),
+                false => quotient,
+            },
+        )
+    }
+
+    /// Euclidean remainder, which is never negative, or `None` if dividing by zero
+    pub fn checked_rem_euclid(&self, rhs: &Int) -> Option<Int> {
+        let rem = self.checked_rem(rhs)?;
+
+        Some(match (rem.is_negative(), rhs.is_negative()) {
+            (true, true) => rem - rhs.clone(),
+            (true, false) => rem + rhs.clone(),
+            (false, _) => rem,
+        })
+    }
+
+    /// Shift left by a non-negative amount, or `None` if the result would be unreasonably large
+    pub fn checked_shl(&self, amount: &Int) -> Option<Int> {
+        if self.is_zero() {
+            return Some(Int::Small(0));
+        }
+
+        let amount = amount.to_u32()?;
+        if self.to_big().bits().saturating_add(amount.into()) > MAX_POW_BITS {
+            return None;
+        }
+
+        if let Int::Small(val) = self {
+            // `1 << 63` would already be negative, so leave that to `BigInt`
+            let pow = (amount < 63).then(|| 1i64 << amount);
+            if let Some(val) = pow.and_then(|pow| val.checked_mul(pow)) {
+                return Some(Int::Small(val));
+            }
+        }
+
+        Some(Int::from(self.to_big() << amount))
+    }
+
+    /// Shift right by a non-negative amount, rounding towards negative infinity like an
+    /// arithmetic shift on a two's complement integer
+    pub fn shr(&self, amount: &Int) -> Int {
+        match (self, amount.to_u32()) {
+            (Int::Small(val), Some(amount)) => Int::Small(val >> amount.min(63)),
+            (Int::Big(val), Some(amount)) => Int::from(val >> amount),
+
+            // Shifting by more bits than could ever exist leaves only the sign
+            (_, None) => Int::Small(if self.is_negative() { -1 } else { 0 }),
+        }
+    }
+
+    /// Raise to a non-negative power, or `None` if the result would be unreasonably large
+    pub fn checked_pow(&self, exp: &Int) -> Option<Int> {
+        // These stay small no matter how large the exponent is
+        match self {
+            Int::Small(0) if exp.is_zero() => return Some(Int::Small(1)),
+            Int::Small(0 | 1) => return Some(self.clone()),
+            Int::Small(-1) => return Some(Int::Small(if exp.to_big().bit(0) { -1 } else { 1 })),
+            _ => {}
+        }
+
+        let exp = exp.to_u32()?;
+        if self.to_big().bits().saturating_mul(exp.into()) > MAX_POW_BITS {
+            return None;
+        }
+
+        if let Int::Small(val) = self {
+            if let Some(val) = val.checked_pow(exp) {
+                return Some(Int::Small(val));
+            }
+        }
+
+        Some(Int::from(num_traits::Pow::pow(self.to_big(), exp)))
+    }
+}
+
+/// Implement an operator by trying the checked `i64` version first, falling back to `BigInt`
+macro_rules! impl_op {
+    ($trait:ident, $method:ident, $checked:ident) => {
+        impl $trait for Int {
+            type Output = Int;
+
+            fn $method(self, rhs: Int) -> Int {
+                if let (Int::Small(lhs), Int::Small(rhs)) = (&self, &rhs) {
+                    if let Some(val) = lhs.$checked(*rhs) {
+                        return Int::Small(val);
+                    }
+                }
+
+                Int::from($trait::$method(self.to_big(), rhs.to_big()))
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, checked_add);
+impl_op!(Sub, sub, checked_sub);
+impl_op!(Mul, mul, checked_mul);
+
+/// Implement a bitwise operator, which can never overflow an `i64`
+macro_rules! impl_bit_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Int {
+            type Output = Int;
+
+            fn $method(self, rhs: Int) -> Int {
+                match (&self, &rhs) {
+                    (Int::Small(lhs), Int::Small(rhs)) => Int::Small($trait::$method(lhs, rhs)),
+                    _ => Int::from($trait::$method(self.to_big(), rhs.to_big())),
+                }
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand);
+impl_bit_op!(BitOr, bitor);
+impl_bit_op!(BitXor, bitxor);
+
+impl Not for Int {
+    type Output = Int;
+
+    fn not(self) -> Int {
+        match self {
+            Int::Small(val) => Int::Small(!val),
+            Int::Big(val) => Int::from(!val),
+        }
+    }
+}
+
+impl Neg for Int {
+    type Output = Int;
+
+    fn neg(self) -> Int {
+        match self {
+            Int::Small(val) => match val.checked_neg() {
+                Some(val) => Int::Small(val),
+                None => Int::from(-BigInt::from(val)),
+            },
+            Int::Big(val) => Int::from(-val),
+        }
+    }
+}
+
+impl From<i32> for Int {
+    fn from(value: i32) -> Self {
+        Int::Small(value.into())
+    }
+}
+
+impl From<i64> for Int {
+    fn from(value: i64) -> Self {
+        Int::Small(value)
+    }
+}
+
+impl From<usize> for Int {
+    fn from(value: usize) -> Self {
+        match i64::try_from(value) {
+            Ok(val) => Int::Small(val),
+            Err(_) => Int::Big(BigInt::from(value)),
+        }
+    }
+}
+
+impl From<BigInt> for Int {
+    fn from(value: BigInt) -> Self {
+        match value.to_i64() {
+            Some(val) => Int::Small(val),
+            None => Int::Big(value),
+        }
+    }
+}
+
+impl FromStr for Int {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<i64>() {
+            Ok(val) => Ok(Int::Small(val)),
+            Err(_) => Ok(Int::from(s.parse::<BigInt>()?)),
+        }
+    }
+}
+
+impl PartialOrd for Int {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Int {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Int::Small(lhs), Int::Small(rhs)) => lhs.cmp(rhs),
+            _ => self.to_big().cmp(&other.to_big()),
+        }
+    }
+}
+
+impl Display for Int {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Int::Small(val) => write!(f, "{}", val),
+            Int::Big(val) => write!(f, "{}", val),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn promotes_on_overflow() {
+        let max = Int::from(i64::MAX);
+
+        assert!(matches!(max.clone() + 1.into(), Int::Big(_)));
+        assert_eq!((max.clone() + 1.into()) - 1.into(), Int::Small(i64::MAX));
+        assert_eq!((max.clone() * 2.into()).to_string(), "18446744073709551614");
+        assert_eq!(-Int::from(i64::MIN), max + 1.into());
+        assert_eq!(
+            Int::from(i64::MIN).checked_div(&(-1).into()),
+            Some(-Int::from(i64::MIN))
+        );
+        assert_eq!(Int::from(1).checked_div(&0.into()), None);
+    }
+
+    #[test]
+    fn parse_and_compare() {
+        let big = "123456789012345678901234567890".parse::<Int>().unwrap();
+
+        assert!(matches!(big, Int::Big(_)));
+        assert_eq!(big.to_string(), "123456789012345678901234567890");
+        assert_eq!("42".parse::<Int>().unwrap(), Int::Small(42));
+        assert!(big > Int::from(i64::MAX));
+        assert!(-big < Int::from(i64::MIN));
+        assert_eq!(
+            Int::from(2).checked_pow(&64.into()).unwrap().to_string(),
+            "18446744073709551616"
+        );
+    }
+
+    #[test]
+    fn division_rounding() {
+        let cases: [(i64, i64); 6] = [(7, 2), (-7, 2), (7, -2), (-7, -2), (6, 3), (-6, 3)];
+
+        for (lhs, rhs) in cases {
+            let (lhs_int, rhs_int) = (Int::from(lhs), Int::from(rhs));
+
+            assert_eq!(lhs_int.checked_rem(&rhs_int), Some((lhs % rhs).into()));
+            assert_eq!(
+                lhs_int.checked_div_floor(&rhs_int),
+                Some(((lhs as f64 / rhs as f64).floor() as i64).into())
+            );
+            assert_eq!(
+                lhs_int.checked_rem_euclid(&rhs_int),
+                Some(lhs.rem_euclid(rhs).into())
+            );
+        }
+
+        let min = Int::from(i64::MIN);
+        assert_eq!(min.checked_rem(&(-1).into()), Some(0.into()));
+        assert_eq!(min.checked_div_floor(&(-1).into()), Some(-min.clone()));
+        assert_eq!(min.checked_rem_euclid(&0.into()), None);
+    }
+
+    #[test]
+    fn bitwise() {
+        let big = Int::from(1).checked_shl(&100.into()).unwrap();
+
+        assert_eq!(Int::from(0b1100) & 0b1010.into(), 0b1000.into());
+        assert_eq!(Int::from(0b1100) | 0b1010.into(), 0b1110.into());
+        assert_eq!(Int::from(0b1100) ^ 0b1010.into(), 0b0110.into());
+        assert_eq!(!Int::from(5), (-6).into());
+        assert_eq!(!big.clone(), -(big.clone() + 1.into()));
+        assert_eq!((big.clone() | 1.into()) & 3.into(), 1.into());
+        assert_eq!(big.to_string(), "1267650600228229401496703205376");
+        assert_eq!(big.shr(&98.into()), 4.into());
+        assert_eq!(Int::from(-7).shr(&1.into()), (-4).into());
+        assert_eq!(Int::from(-7).shr(&1000.into()), (-1).into());
+        assert_eq!(Int::from(7).shr(&(big.clone())), 0.into());
+        assert_eq!(Int::from(-1).checked_shl(&63.into()), Some(i64::MIN.into()));
+        assert_eq!(
+            Int::from(1).checked_shl(&63.into()).unwrap().to_string(),
+            "9223372036854775808"
+        );
+        assert_eq!(Int::from(1).checked_shl(&big), None);
+    }
+
+    #[test]
+    fn checked_pow_limits() {
+        let huge = "100000000000000000000".parse::<Int>().unwrap();
+
+        assert_eq!(Int::from(1).checked_pow(&huge), Some(1.into()));
+        assert_eq!(Int::from(-1).checked_pow(&huge), Some(1.into()));
+        assert_eq!(
+            Int::from(-1).checked_pow(&(huge.clone() + 1.into())),
+            Some((-1).into())
+        );
+        assert_eq!(Int::from(0).checked_pow(&0.into()), Some(1.into()));
+        assert_eq!(Int::from(2).checked_pow(&huge), None);
+        assert_eq!(Int::from(10).checked_pow(&10_000_000.into()), None);
+    }
+}
@@ -1,13 +1,21 @@
 mod error;
 mod expr;
+mod int;
 mod op;
+mod pattern;
+mod range;
+mod span;
 mod stmt;
 mod token;
 mod value;
 
 pub use error::*;
 pub use expr::*;
+pub use int::*;
 pub use op::*;
+pub use pattern::*;
+pub use range::*;
+pub use span::*;
 pub use stmt::*;
 pub use token::*;
 pub use value::*;
@@ -1,4 +1,5 @@
 use super::*;
+use std::fmt::{self, Display, Formatter};
 
 /// An unary operator (e.g. -[int], +[int])
 #[derive(Debug, PartialEq)]
@@ -6,6 +7,14 @@ pub enum UnOp {
     Plus,
     Minus,
     Negate,
+    BitNot,
+}
+
+/// An update operator (e.g. ++[ident], [ident]--)
+#[derive(Debug, PartialEq)]
+pub enum UpdOp {
+    Inc,
+    Dec,
 }
 
 /// A binary operator (e.g. [int] + [int])
@@ -15,9 +24,23 @@ pub enum BinOp {
     Sub,
     Mul,
     Div,
+    FloatDiv,
+    FloorDiv,
+    Rem,
+    Mod,
     Pow,
 }
 
+/// A bitwise operator on integers (e.g. [int] & [int])
+#[derive(Debug, PartialEq)]
+pub enum BitOp {
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+}
+
 /// A logical operator (e.g. [bool] && [bool])
 #[derive(Debug, PartialEq)]
 pub enum LogOp {
@@ -34,6 +57,23 @@ pub enum RelOp {
     Lt,
     Gte,
     Lte,
+    In,
+}
+
+impl Display for BinOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BinOp::Add => write!(f, "+"),
+            BinOp::Sub => write!(f, "-"),
+            BinOp::Mul => write!(f, "*"),
+            BinOp::Div => write!(f, "/"),
+            BinOp::FloatDiv => write!(f, "/."),
+            BinOp::FloorDiv => write!(f, "//"),
+            BinOp::Rem => write!(f, "%"),
+            BinOp::Mod => write!(f, "mod"),
+            BinOp::Pow => write!(f, "^"),
+        }
+    }
 }
 
 impl From<Token> for UnOp {
@@ -42,6 +82,17 @@ impl From<Token> for UnOp {
             Token::Hyphen => UnOp::Minus,
             Token::Plus => UnOp::Plus,
             Token::Bang => UnOp::Negate,
+            Token::Tilde => UnOp::BitNot,
+            _ => panic!("Invalid token"),
+        }
+    }
+}
+
+impl From<Token> for UpdOp {
+    fn from(value: Token) -> Self {
+        match value {
+            Token::PlusPlus => UpdOp::Inc,
+            Token::HyphenHyphen => UpdOp::Dec,
             _ => panic!("Invalid token"),
         }
     }
@@ -50,16 +101,45 @@ impl From<Token> for UnOp {
 impl From<Token> for BinOp {
     fn from(value: Token) -> Self {
         match value {
-            Token::Plus => BinOp::Add,
-            Token::Hyphen => BinOp::Sub,
-            Token::Star => BinOp::Mul,
-            Token::Divide => BinOp::Div,
-            Token::Caret => BinOp::Pow,
+            Token::Plus | Token::PlusEqual => BinOp::Add,
+            Token::Hyphen | Token::HyphenEqual => BinOp::Sub,
+            Token::Star | Token::StarEqual => BinOp::Mul,
+            Token::Divide | Token::DivideEqual => BinOp::Div,
+            Token::DivideDot => BinOp::FloatDiv,
+            Token::DivideDivide => BinOp::FloorDiv,
+            Token::Percent => BinOp::Rem,
+            Token::Mod => BinOp::Mod,
+            Token::Caret | Token::CaretEqual => BinOp::Pow,
+            _ => panic!("Invalid token"),
+        }
+    }
+}
+
+impl From<Token> for BitOp {
+    fn from(value: Token) -> Self {
+        match value {
+            Token::Ampersand => BitOp::And,
+            Token::Pipe => BitOp::Or,
+            Token::CaretCaret => BitOp::Xor,
+            Token::ShiftLeft => BitOp::Shl,
+            Token::ShiftRight => BitOp::Shr,
             _ => panic!("Invalid token"),
         }
     }
 }
 
+impl Display for BitOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BitOp::And => write!(f, "&"),
+            BitOp::Or => write!(f, "|"),
+            BitOp::Xor => write!(f, "^^"),
+            BitOp::Shl => write!(f, "<<"),
+            BitOp::Shr => write!(f, ">>"),
+        }
+    }
+}
+
 impl From<Token> for LogOp {
     fn from(value: Token) -> Self {
         match value {
@@ -79,7 +159,22 @@ impl From<Token> for RelOp {
             Token::RelLt => RelOp::Lt,
             Token::RelGte => RelOp::Gte,
             Token::RelLte => RelOp::Lte,
+            Token::In => RelOp::In,
             _ => panic!("Invalid token"),
         }
     }
 }
+
+impl Display for RelOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RelOp::Eq => write!(f, "=="),
+            RelOp::Ne => write!(f, "!="),
+            RelOp::Gt => write!(f, ">"),
+            RelOp::Lt => write!(f, "<"),
+            RelOp::Gte => write!(f, ">="),
+            RelOp::Lte => write!(f, "<="),
+            RelOp::In => write!(f, "in"),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::rc::Rc;
+
+use super::*;
+
+/// A pattern destructures a value, binding its parts to names
+#[derive(Debug)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub span: Span,
+}
+
+/// The different kinds of patterns
+#[derive(Debug, PartialEq)]
+pub enum PatternKind {
+    /// Binds the whole value to a name (e.g. `x`)
+    Ident(String),
+
+    /// Matches any value without binding it (`_`)
+    Wildcard,
+
+    /// Matches a value equal to a literal (e.g. `0`, `-1.5`, `"hi"`, `true`)
+    Literal(Value),
+
+    /// Matches a variant of an enum by name, along with its values (e.g. `Circle(r)`, `Empty`)
+    Variant { ident: String, fields: Vec<Pattern> },
+
+    /// Matches a tuple with exactly as many elements (e.g. `(a, b)`)
+    Tuple(Vec<Pattern>),
+
+    /// Matches a list, optionally binding any remaining elements to a list (e.g. `[a, ...rest]`)
+    List {
+        items: Vec<Pattern>,
+        rest: Option<Rc<Pattern>>,
+    },
+}
+
+impl Pattern {
+    pub fn new(kind: PatternKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    /// Create a pattern binding a single name
+    pub fn ident(ident: String, span: Span) -> Self {
+        Self::new(PatternKind::Ident(ident), span)
+    }
+
+    /// Does the pattern match every value?
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(self.kind, PatternKind::Ident(_) | PatternKind::Wildcard)
+    }
+}
+
+// Patterns are compared by structure alone, regardless of where they are in the source
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+};
+
+use super::Int;
+
+/// A lazy sequence of integers from `start` towards `end`, counting by `step`
+#[derive(Debug, Clone)]
+pub struct Range {
+    pub start: Int,
+    pub end: Int,
+    pub step: Int,
+    pub inclusive: bool,
+}
+
+impl Range {
+    /// Create a range, or `None` if the step is zero
+    pub fn new(start: Int, end: Int, step: Int, inclusive: bool) -> Option<Self> {
+        (!step.is_zero()).then_some(Self {
+            start,
+            end,
+            step,
+            inclusive,
+        })
+    }
+
+    /// Get the first value past the end of the range, as if the end were exclusive
+    fn stop(&self) -> Int {
+        match (self.inclusive, self.step.is_positive()) {
+            (false, _) => self.end.clone(),
+            (true, true) => self.end.clone() + 1.into(),
+            (true, false) => self.end.clone() - 1.into(),
+        }
+    }
+
+    /// Get the number of values in the range
+    pub fn len(&self) -> Int {
+        // Distance to the stop in the direction of the step, rounded up to a whole step
+        let (distance, step) = match self.step.is_positive() {
+            true => (self.stop() - self.start.clone(), self.step.clone()),
+            false => (self.start.clone() - self.stop(), -self.step.clone()),
+        };
+
+        match distance.is_positive() {
+            true => (distance + step.clone() - 1.into())
+                .checked_div_floor(&step)
+                .expect("step is never zero"),
+            false => 0.into(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len().is_zero()
+    }
+
+    /// Get the value at a position, which must be within bounds
+    pub fn nth(&self, index: &Int) -> Int {
+        self.start.clone() + index.clone() * self.step.clone()
+    }
+
+    /// Get the values between two positions, which must be within bounds
+    pub fn slice(&self, start: &Int, end: &Int) -> Self {
+        Self {
+            start: self.nth(start),
+            end: self.nth(end.max(start)),
+            step: self.step.clone(),
+            inclusive: false,
+        }
+    }
+
+    /// Check if a value is one of the values in the range
+    pub fn contains(&self, val: &Int) -> bool {
+        let within = match self.step.is_positive() {
+            true => *val >= self.start && *val < self.stop(),
+            false => *val <= self.start && *val > self.stop(),
+        };
+
+        within
+            && (val.clone() - self.start.clone())
+                .checked_rem(&self.step)
+                .is_some_and(|rem| rem.is_zero())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Int> + '_ {
+        let stop = self.stop();
+        let ascending = self.step.is_positive();
+
+        std::iter::successors(Some(self.start.clone()), |val| {
+            Some(val.clone() + self.step.clone())
+        })
+        .take_while(move |val| match ascending {
+            true => *val < stop,
+            false => *val > stop,
+        })
+    }
+}
+
+// Ranges are compared by the values they contain, like lists (e.g. `0..3 == 0..=2`). Only the
+// first two values and the length are needed to order them lexicographically.
+impl Ord for Range {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (len, other_len) = (self.len(), other.len());
+
+        if len.is_zero() || other_len.is_zero() {
+            return len.cmp(&other_len);
+        }
+
+        match self.start.cmp(&other.start) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+
+        if len > 1.into() && other_len > 1.into() {
+            match self.step.cmp(&other.step) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            }
+        }
+
+        len.cmp(&other_len)
+    }
+}
+
+impl PartialOrd for Range {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Range {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Range {}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.inclusive {
+            true => write!(f, "{}..={}", self.start, self.end)?,
+            false => write!(f, "{}..{}", self.start, self.end)?,
+        }
+
+        match self.step == 1.into() {
+            true => Ok(()),
+            false => write!(f, " step {}", self.step),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: i64, end: i64, step: i64, inclusive: bool) -> Range {
+        Range::new(start.into(), end.into(), step.into(), inclusive).unwrap()
+    }
+
+    fn values(range: &Range) -> Vec<i64> {
+        range.iter().map(|val| val.to_i64().unwrap()).collect()
+    }
+
+    #[test]
+    fn length_matches_values() {
+        [
+            range(0, 10, 1, false),
+            range(0, 10, 1, true),
+            range(0, 10, 3, false),
+            range(0, 9, 3, true),
+            range(10, 0, -3, false),
+            range(10, 0, -2, true),
+            range(5, 5, 1, false),
+            range(5, 5, 1, true),
+            range(5, 0, 1, false),
+        ]
+        .iter()
+        .for_each(|range| {
+            let values = values(range);
+
+            assert_eq!(
+                range.len(),
+                (values.len() as i64).into(),
+                "range = {}",
+                range
+            );
+            assert!(values.iter().all(|val| range.contains(&(*val).into())));
+        });
+    }
+
+    #[test]
+    fn contains() {
+        let range = range(10, 0, -3, true);
+
+        assert_eq!(values(&range), vec![10, 7, 4, 1]);
+        assert!(range.contains(&7.into()));
+        assert!(!range.contains(&8.into()));
+        assert!(!range.contains(&(-2).into()));
+        assert!(!range.contains(&13.into()));
+    }
+
+    #[test]
+    fn ordering() {
+        assert_eq!(range(0, 3, 1, false), range(0, 2, 1, true));
+        assert_eq!(range(4, 0, 1, false), range(9, 2, 1, false));
+        assert_eq!(range(1, 2, 1, false), range(1, 2, 5, false));
+        assert!(range(0, 3, 1, false) < range(0, 4, 1, false));
+        assert!(range(0, 3, 1, false) < range(0, 3, 2, false));
+        assert!(range(0, 3, 1, false) > range(0, 0, 1, false));
+        assert!(range(1, 0, -1, true) < range(2, 0, -1, true));
+    }
+}
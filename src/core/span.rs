@@ -0,0 +1,58 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A region of the source code, used to point errors at where they came from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the first character
+    pub start: usize,
+
+    /// Byte offset just past the last character
+    pub end: usize,
+
+    /// Line of the first character, starting at 1
+    pub line: usize,
+
+    /// Column of the first character, starting at 1
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Self {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// Create a span covering both this span and another one
+    pub fn to(self, other: Span) -> Self {
+        let (first, last) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        Self {
+            end: first.end.max(last.end),
+            ..first
+        }
+    }
+
+    /// Create an empty span directly after this one (e.g. for pointing at the end of input)
+    pub fn after(self) -> Self {
+        Self {
+            start: self.end,
+            end: self.end,
+            line: self.line,
+            column: self.column + (self.end - self.start),
+        }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -2,21 +2,84 @@ use super::*;
 use std::rc::Rc;
 
 /// A statement can be an operation upon an expression, or just a single expression
+#[derive(Debug)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+/// The different kinds of statements
 #[derive(Debug, PartialEq)]
-pub enum Stmt {
-    /// An if statement (e.g. `if <expr> <stmt> [<else> <stmt>]`)
-    If {
+pub enum StmtKind {
+    /// A let statement, binding every name in the pattern (e.g. `let x = 10;`, `let (a, b) = pair;`)
+    Let { pattern: Pattern, expr: Expr },
+
+    /// An assignment to an existing variable (e.g. `x = 10;`)
+    Assign { ident: String, expr: Expr },
+
+    /// An assignment to an element of a list or map in a variable (e.g. `m["a"][0] = 10;`)
+    SetIndex {
+        ident: String,
+        path: Vec<Rc<Expr>>,
+        expr: Expr,
+    },
+
+    /// Removal of an element from a list or map in a variable (e.g. `delete m["a"]`)
+    Delete { ident: String, path: Vec<Rc<Expr>> },
+
+    /// A struct type declaration (e.g. `struct Point { x, y }`)
+    Struct { ident: String, fields: Vec<String> },
+
+    /// An enum type declaration (e.g. `enum Shape { Circle(r), Rect(w, h), Empty }`)
+    Enum {
+        ident: String,
+        variants: Vec<(String, Vec<String>)>,
+    },
+
+    /// A function declaration (e.g. `fn foo(x) { x + 3 }`)
+    Fn {
+        ident: String,
+        params: Vec<String>,
+        body: Rc<Stmt>,
+    },
+
+    /// A while loop (e.g. `while x < 5 { x += 1 }`)
+    While { test: Expr, body: Rc<Stmt> },
+
+    /// A C-style for loop (e.g. `for x = 0; x <= 5; x += 1 { x }`)
+    For {
+        init: Rc<Stmt>,
         test: Expr,
-        branch: Rc<Stmt>,
-        else_branch: Option<Rc<Stmt>>,
+        update: Rc<Stmt>,
+        body: Rc<Stmt>,
+    },
+
+    /// A loop over the items of a list, yielding a list of results (e.g. `for x in [1, 2] { x * 2 }`)
+    ForIn {
+        ident: String,
+        iter: Expr,
+        body: Rc<Stmt>,
     },
 
-    /// A let statement (e.g. `let x = 10;`)
-    Let { ident: String, expr: Expr },
+    /// Exit the nearest enclosing loop
+    Break,
 
-    /// A group of statements (e.g. `{ let x = 1; x + 2 }`)
-    Block(Vec<Stmt>),
+    /// Skip to the next iteration of the nearest enclosing loop
+    Continue,
 
     /// A single expression
     Expr(Expr),
 }
+
+impl Stmt {
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+// Statements are compared by structure alone, regardless of where they are in the source
+impl PartialEq for Stmt {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
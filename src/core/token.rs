@@ -1,15 +1,32 @@
 use std::fmt::{self, Display, Formatter};
 
+use super::{Int, Span};
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     // Values
-    Integer(i32),
+    Integer(Int),
+    Float(f64),
+    Str(String),
+    Interpolated(Vec<StrPart>),
     Ident(String),
 
     // Keywords
     If,
     Else,
     Let,
+    Fn,
+    While,
+    For,
+    In,
+    Break,
+    Continue,
+    Delete,
+    Struct,
+    Enum,
+    Match,
+    Mod,
+    Step,
     True,
     False,
 
@@ -17,6 +34,14 @@ pub enum Token {
     Or,
     And,
 
+    // Bitwise
+    Ampersand,
+    Pipe,
+    CaretCaret,
+    Tilde,
+    ShiftLeft,
+    ShiftRight,
+
     // Relational
     RelEq,
     RelNe,
@@ -30,8 +55,22 @@ pub enum Token {
     Hyphen,
     Star,
     Divide,
+    DivideDot,
+    DivideDivide,
+    Percent,
     Caret,
 
+    // Assignment
+    PlusEqual,
+    HyphenEqual,
+    StarEqual,
+    DivideEqual,
+    CaretEqual,
+
+    // Update
+    PlusPlus,
+    HyphenHyphen,
+
     // Brackets
     LeftParen,
     RightParen,
@@ -40,23 +79,85 @@ pub enum Token {
     LeftBrace,
     RightBrace,
 
+    // Dots
+    Dot,
+    DotDot,
+    DotDotEqual,
+    DotDotDot,
+
     // Misc
     Bang,
     Equal,
+    FatArrow,
     Comma,
+    Colon,
     Semicolon,
 }
 
+/// A piece of an interpolated string literal
+#[derive(Debug, PartialEq, Clone)]
+pub enum StrPart {
+    Literal(String),
+
+    /// Tokens of an expression embedded between braces
+    Tokens(Vec<SpannedToken>),
+}
+
+/// A token along with where it was found in the source code
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+impl SpannedToken {
+    pub fn new(token: Token, span: Span) -> Self {
+        Self { token, span }
+    }
+}
+
 impl Display for Token {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Token::Integer(value) => write!(f, "{}", value),
+            Token::Float(value) => write!(f, "{:?}", value),
+            Token::Str(value) => write!(f, "{:?}", value),
+            Token::Interpolated(parts) => {
+                write!(f, "\"")?;
+                for part in parts {
+                    match part {
+                        StrPart::Literal(value) => write!(f, "{}", value.escape_debug())?,
+                        StrPart::Tokens(tokens) => write!(
+                            f,
+                            "{{{}}}",
+                            tokens
+                                .iter()
+                                .map(|token| token.token.to_string())
+                                .collect::<Vec<_>>()
+                                .join(" ")
+                        )?,
+                    }
+                }
+                write!(f, "\"")
+            }
             Token::Ident(value) => write!(f, "{}", value),
 
             // Keywords
             Token::If => write!(f, "if"),
             Token::Else => write!(f, "else"),
             Token::Let => write!(f, "let"),
+            Token::Fn => write!(f, "fn"),
+            Token::While => write!(f, "while"),
+            Token::For => write!(f, "for"),
+            Token::In => write!(f, "in"),
+            Token::Break => write!(f, "break"),
+            Token::Continue => write!(f, "continue"),
+            Token::Delete => write!(f, "delete"),
+            Token::Struct => write!(f, "struct"),
+            Token::Enum => write!(f, "enum"),
+            Token::Match => write!(f, "match"),
+            Token::Mod => write!(f, "mod"),
+            Token::Step => write!(f, "step"),
             Token::True => write!(f, "true"),
             Token::False => write!(f, "false"),
 
@@ -64,6 +165,14 @@ impl Display for Token {
             Token::Or => write!(f, "||"),
             Token::And => write!(f, "&&"),
 
+            // Bitwise
+            Token::Ampersand => write!(f, "&"),
+            Token::Pipe => write!(f, "|"),
+            Token::CaretCaret => write!(f, "^^"),
+            Token::Tilde => write!(f, "~"),
+            Token::ShiftLeft => write!(f, "<<"),
+            Token::ShiftRight => write!(f, ">>"),
+
             // Relational
             Token::RelEq => write!(f, "=="),
             Token::RelNe => write!(f, "!="),
@@ -77,8 +186,22 @@ impl Display for Token {
             Token::Hyphen => write!(f, "-"),
             Token::Star => write!(f, "*"),
             Token::Divide => write!(f, "/"),
+            Token::DivideDot => write!(f, "/."),
+            Token::DivideDivide => write!(f, "//"),
+            Token::Percent => write!(f, "%"),
             Token::Caret => write!(f, "^"),
 
+            // Assignment
+            Token::PlusEqual => write!(f, "+="),
+            Token::HyphenEqual => write!(f, "-="),
+            Token::StarEqual => write!(f, "*="),
+            Token::DivideEqual => write!(f, "/="),
+            Token::CaretEqual => write!(f, "^="),
+
+            // Update
+            Token::PlusPlus => write!(f, "++"),
+            Token::HyphenHyphen => write!(f, "--"),
+
             // Brackets
             Token::LeftParen => write!(f, "("),
             Token::RightParen => write!(f, ")"),
@@ -87,22 +210,52 @@ impl Display for Token {
             Token::LeftBrace => write!(f, "{{"),
             Token::RightBrace => write!(f, "}}"),
 
+            // Dots
+            Token::Dot => write!(f, "."),
+            Token::DotDot => write!(f, ".."),
+            Token::DotDotEqual => write!(f, "..="),
+            Token::DotDotDot => write!(f, "..."),
+
             // Misc
             Token::Bang => write!(f, "!"),
             Token::Equal => write!(f, "="),
+            Token::FatArrow => write!(f, "=>"),
             Token::Comma => write!(f, ","),
+            Token::Colon => write!(f, ":"),
             Token::Semicolon => write!(f, ";"),
         }
     }
 }
 
 impl Token {
+    /// Get the token closing this one, if it is an opening delimiter
+    pub fn closing_delimiter(&self) -> Option<Self> {
+        Some(match self {
+            Token::LeftParen => Token::RightParen,
+            Token::LeftBracket => Token::RightBracket,
+            Token::LeftBrace => Token::RightBrace,
+            _ => return None,
+        })
+    }
+
     /// Attempt to map a reserved keyword to its token
     pub fn map_keyword(text: &str) -> Option<Self> {
         Some(match text {
             "if" => Token::If,
             "else" => Token::Else,
             "let" => Token::Let,
+            "fn" => Token::Fn,
+            "while" => Token::While,
+            "for" => Token::For,
+            "in" => Token::In,
+            "break" => Token::Break,
+            "continue" => Token::Continue,
+            "delete" => Token::Delete,
+            "struct" => Token::Struct,
+            "enum" => Token::Enum,
+            "match" => Token::Match,
+            "mod" => Token::Mod,
+            "step" => Token::Step,
             "true" => Token::True,
             "false" => Token::False,
             _ => return None,
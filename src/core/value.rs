@@ -1,55 +1,430 @@
-use std::fmt::Display;
+use std::{
+    cell::RefCell,
+    fmt::{self, Debug, Display},
+    rc::Rc,
+};
 
-use super::IntyResult;
+use super::{Int, IntyError, IntyResult, Range, Span, Stmt};
+use crate::eval::{equal, Environment, Truthiness};
 
 /// Internal values for evaluation
 #[derive(Debug, PartialEq, Clone)]
 pub enum Value {
-    Integer(i32),
+    Unit,
+    Integer(Int),
+    Float(f64),
     Bool(bool),
+    Str(String),
     List(Vec<Value>),
+    Tuple(Vec<Value>),
+    Range(Range),
+    Map(Map),
+    Struct(Rc<StructType>),
+    Record(Record),
+    Enum(Rc<EnumType>),
+    Variant(Variant),
+    Constructor(Rc<EnumType>, usize),
+    Function(Function),
+}
+
+/// A struct type declared with `struct`, listing the names of its fields
+#[derive(Debug, PartialEq)]
+pub struct StructType {
+    pub ident: String,
+    pub fields: Vec<String>,
+}
+
+/// An instance of a struct type, with a value for each of its fields in declaration order
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub ty: Rc<StructType>,
+    pub values: Vec<Value>,
+}
+
+impl Record {
+    pub fn get(&self, field: &str) -> Option<&Value> {
+        let index = self.ty.fields.iter().position(|f| f == field)?;
+        self.values.get(index)
+    }
+}
+
+// Records are equal if their struct types match by name and fields, and their values are equal
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.ty == other.ty && self.values == other.values
+    }
+}
+
+impl Display for StructType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.fields.is_empty() {
+            true => write!(f, "struct {} {{}}", self.ident),
+            false => write!(f, "struct {} {{ {} }}", self.ident, self.fields.join(", ")),
+        }
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.values.is_empty() {
+            return write!(f, "{} {{}}", self.ty.ident);
+        }
+
+        write!(
+            f,
+            "{} {{ {} }}",
+            self.ty.ident,
+            self.ty
+                .fields
+                .iter()
+                .zip(&self.values)
+                .map(|(field, value)| format!("{}: {}", field, value.to_nested_string()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// An enum type declared with `enum`, listing its variants
+#[derive(Debug, PartialEq)]
+pub struct EnumType {
+    pub ident: String,
+    pub variants: Vec<VariantType>,
+}
+
+impl EnumType {
+    /// Create an enum type from the variants in its declaration, along with the names they hold
+    pub fn new(ident: &str, variants: &[(String, Vec<String>)]) -> Self {
+        Self {
+            ident: ident.to_string(),
+            variants: variants
+                .iter()
+                .map(|(ident, fields)| VariantType {
+                    ident: ident.clone(),
+                    fields: fields.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A variant of an enum type, naming the values it holds
+#[derive(Debug, PartialEq)]
+pub struct VariantType {
+    pub ident: String,
+    pub fields: Vec<String>,
+}
+
+/// An instance of a variant of an enum type, with a value for each of its fields
+#[derive(Debug, Clone)]
+pub struct Variant {
+    pub ty: Rc<EnumType>,
+    pub index: usize,
+    pub values: Vec<Value>,
+}
+
+impl Variant {
+    pub fn ident(&self) -> &str {
+        &self.ty.variants[self.index].ident
+    }
+}
+
+// Variants are equal if their enum types match by name and variants, and their values are equal
+impl PartialEq for Variant {
+    fn eq(&self, other: &Self) -> bool {
+        self.ty == other.ty && self.index == other.index && self.values == other.values
+    }
+}
+
+impl Display for EnumType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.variants.is_empty() {
+            true => write!(f, "enum {} {{}}", self.ident),
+            false => write!(
+                f,
+                "enum {} {{ {} }}",
+                self.ident,
+                self.variants
+                    .iter()
+                    .map(|variant| variant.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl Display for VariantType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.fields.is_empty() {
+            true => write!(f, "{}", self.ident),
+            false => write!(f, "{}({})", self.ident, self.fields.join(", ")),
+        }
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.values.is_empty() {
+            return write!(f, "{}", self.ident());
+        }
+
+        write!(
+            f,
+            "{}({})",
+            self.ident(),
+            self.values
+                .iter()
+                .map(|value| value.to_nested_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// A function along with the environment it was defined in
+#[derive(Clone)]
+pub struct Function {
+    pub ident: Option<String>,
+    pub params: Vec<String>,
+    pub body: Rc<Stmt>,
+    pub env: Rc<RefCell<Environment>>,
+}
+
+/// Keys mapped to values, kept in the order they were first inserted. Keys are found the same
+/// way `==` compares values, so `1` and `1.0` are the same key.
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    entries: Vec<(Value, Value)>,
+}
+
+impl Map {
+    /// Index of the entry with a key equal to the given one
+    fn position(&self, key: &Value) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|(k, _)| equal(k, key, Span::default()))
+    }
+
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        self.position(key).map(|index| &self.entries[index].1)
+    }
+
+    pub fn get_mut(&mut self, key: &Value) -> Option<&mut Value> {
+        self.position(key).map(|index| &mut self.entries[index].1)
+    }
+
+    pub fn contains_key(&self, key: &Value) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Set the value of a key, which keeps its place if it is already in the map
+    pub fn insert(&mut self, key: Value, value: Value) {
+        match self.get_mut(&key) {
+            Some(existing) => *existing = value,
+            None => self.entries.push((key, value)),
+        }
+    }
+
+    pub fn remove(&mut self, key: &Value) -> Option<Value> {
+        let index = self.position(key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(Value, Value)> {
+        self.entries.iter()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &Value> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// Maps are equal if they have the same entries, regardless of their order
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .entries
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "{{:}}");
+        }
+
+        write!(
+            f,
+            "{{{}}}",
+            self.entries
+                .iter()
+                .map(|(k, v)| format!("{}: {}", k.to_nested_string(), v.to_nested_string()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
 }
 
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Value::Unit => write!(f, "()"),
             Value::Integer(val) => write!(f, "{}", val),
+            // Debug formatting always keeps a decimal point or exponent, so floats read back as floats
+            Value::Float(val) => write!(f, "{:?}", val),
             Value::Bool(val) => write!(f, "{}", val),
+            Value::Str(val) => write!(f, "{}", val),
             Value::List(val) => {
                 write!(
                     f,
                     "[{}]",
-                    val.into_iter()
-                        .map(|v| v.to_string())
+                    val.iter()
+                        .map(|v| v.to_nested_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+            // A single element keeps its trailing comma, so it can't be mistaken for parentheses
+            Value::Tuple(val) if val.len() == 1 => write!(f, "({},)", val[0].to_nested_string()),
+            Value::Tuple(val) => {
+                write!(
+                    f,
+                    "({})",
+                    val.iter()
+                        .map(|v| v.to_nested_string())
                         .collect::<Vec<_>>()
                         .join(", ")
                 )
             }
+            Value::Range(val) => write!(f, "{}", val),
+            Value::Map(val) => write!(f, "{}", val),
+            Value::Struct(val) => write!(f, "{}", val),
+            Value::Record(val) => write!(f, "{}", val),
+            Value::Enum(val) => write!(f, "{}", val),
+            Value::Variant(val) => write!(f, "{}", val),
+            Value::Constructor(ty, index) => write!(f, "fn {}", ty.variants[*index]),
+            Value::Function(func) => match &func.ident {
+                Some(ident) => write!(f, "fn {}({})", ident, func.params.join(", ")),
+                None => write!(f, "fn({})", func.params.join(", ")),
+            },
         }
     }
 }
 
+// The captured environment may contain the function itself, so it is left out
+impl Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function")
+            .field("ident", &self.ident)
+            .field("params", &self.params)
+            .field("body", &self.body)
+            .finish_non_exhaustive()
+    }
+}
+
+// Functions are only equal if they are the same definition in the same environment
+impl PartialEq for Function {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.body, &other.body) && Rc::ptr_eq(&self.env, &other.env)
+    }
+}
+
 impl Value {
-    pub fn try_parse_int(&self) -> IntyResult<i32> {
+    /// Format the value as it appears inside of another value, where strings are quoted
+    pub fn to_nested_string(&self) -> String {
         match self {
-            Value::Integer(val) => Ok(*val),
-            _ => {
-                return Err(super::IntyError::TypeError {
-                    message: format!("{} is not an integer", self),
-                })
-            }
+            Value::Str(val) => format!("{:?}", val),
+            _ => self.to_string(),
         }
     }
 
-    pub fn try_parse_bool(&self) -> IntyResult<bool> {
+    /// Get the name of the value's type, as used in error messages
+    pub fn type_name(&self) -> &'static str {
         match self {
-            Value::Bool(val) => Ok(*val),
-            Value::Integer(val) => Ok(*val > 0),
-            _ => {
-                return Err(super::IntyError::TypeError {
-                    message: format!("{} is not a boolean", self),
-                })
-            }
+            Value::Unit => "unit",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "boolean",
+            Value::Str(_) => "string",
+            Value::List(_) => "list",
+            Value::Tuple(_) => "tuple",
+            Value::Range(_) => "range",
+            Value::Map(_) => "map",
+            Value::Struct(_) => "struct",
+            Value::Record(_) => "record",
+            Value::Enum(_) => "enum",
+            Value::Variant(_) => "variant",
+            Value::Constructor(..) => "constructor",
+            Value::Function(_) => "function",
+        }
+    }
+
+    pub fn try_parse_int(&self, span: Span) -> IntyResult<&Int> {
+        match self {
+            Value::Integer(val) => Ok(val),
+            _ => Err(IntyError::TypeError {
+                message: format!("{} is not an integer", self),
+                span,
+            }),
+        }
+    }
+
+    /// Get the value as a float, converting integers
+    pub fn try_parse_float(&self, span: Span) -> IntyResult<f64> {
+        match self {
+            Value::Integer(val) => Ok(val.to_f64()),
+            Value::Float(val) => Ok(*val),
+            _ => Err(IntyError::TypeError {
+                message: format!("{} is not a number", self),
+                span,
+            }),
+        }
+    }
+
+    pub fn try_parse_bool(&self, truthiness: Truthiness, span: Span) -> IntyResult<bool> {
+        match (self, truthiness) {
+            (Value::Bool(val), _) => Ok(*val),
+            (Value::Integer(val), Truthiness::Legacy) => Ok(val.is_positive()),
+            (Value::Integer(val), Truthiness::Conventional) => Ok(!val.is_zero()),
+            (Value::Float(val), Truthiness::Conventional) => Ok(*val != 0.0),
+            _ => Err(IntyError::TypeError {
+                message: format!("{} is not a boolean", self),
+                span,
+            }),
+        }
+    }
+
+    pub fn try_iter(&self, span: Span) -> IntyResult<Box<dyn Iterator<Item = Value> + '_>> {
+        match self {
+            Value::List(val) | Value::Tuple(val) => Ok(Box::new(val.iter().cloned())),
+            Value::Range(val) => Ok(Box::new(val.iter().map(Value::Integer))),
+            Value::Map(val) => Ok(Box::new(val.keys().cloned())),
+            _ => Err(IntyError::TypeError {
+                message: format!("{} is not iterable", self),
+                span,
+            }),
+        }
+    }
+
+    pub fn try_parse_function(&self, span: Span) -> IntyResult<&Function> {
+        match self {
+            Value::Function(func) => Ok(func),
+            _ => Err(IntyError::TypeError {
+                message: format!("{} is not a function", self),
+                span,
+            }),
         }
     }
 }
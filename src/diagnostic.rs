@@ -0,0 +1,365 @@
+use crate::core::*;
+use std::fmt::Write;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// An error prepared for display, pointing at the source code that caused it
+#[derive(Debug, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Option<Span>,
+    pub labels: Vec<Label>,
+    pub help: Option<String>,
+}
+
+/// A message attached to a region of the source code
+#[derive(Debug, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+    pub primary: bool,
+}
+
+impl Label {
+    fn primary(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+            primary: true,
+        }
+    }
+
+    fn secondary(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+            primary: false,
+        }
+    }
+}
+
+impl From<&IntyError> for Diagnostic {
+    fn from(err: &IntyError) -> Self {
+        let span = err.span();
+        let mut labels = Vec::new();
+        let mut help = None;
+
+        match err {
+            IntyError::UnclosedDelimiterError {
+                open,
+                expected,
+                open_span,
+                span,
+            } => {
+                let name = delimiter_name(open);
+
+                labels.push(Label::secondary(
+                    *open_span,
+                    format!("{} opened here", name),
+                ));
+                labels.push(Label::primary(*span, format!("expected `{}`", expected)));
+                help = Some(format!(
+                    "expected `{}` to close {} opened here",
+                    expected, name
+                ));
+            }
+            IntyError::ExpectedTokenError { expected, span, .. } => {
+                labels.push(Label::primary(*span, format!("expected `{}`", expected)))
+            }
+            IntyError::UnexpectedChar { span, .. } => {
+                labels.push(Label::primary(*span, "unexpected character"))
+            }
+            IntyError::UnknownIdentifier { span, .. } => {
+                labels.push(Label::primary(*span, "not found in this scope"))
+            }
+            IntyError::DivideByZeroError { span } => {
+                labels.push(Label::primary(*span, "division by zero"))
+            }
+            IntyError::IndexOutOfBounds { length, span, .. } => {
+                labels.push(Label::primary(*span, format!("length is {}", length)))
+            }
+            IntyError::UnknownField { ty, span, .. } => {
+                labels.push(Label::primary(*span, format!("not a field of {}", ty)))
+            }
+            IntyError::NonExhaustiveMatch { missing, span, .. } => {
+                labels.push(Label::primary(
+                    *span,
+                    format!("{} not covered", missing.join(", ")),
+                ));
+                help = Some("add an arm for each missing variant, or a `_` arm".to_string());
+            }
+            IntyError::ArityError { expected, span, .. } => labels.push(Label::primary(
+                *span,
+                format!("expected {} arguments", expected),
+            )),
+            _ => {
+                if let Some(span) = span {
+                    labels.push(Label::primary(span, ""));
+                }
+            }
+        }
+
+        Self {
+            message: err.to_string(),
+            span,
+            labels,
+            help,
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Render the diagnostic in a compiler-like format, underlining the labeled source lines
+    pub fn render(&self, source_name: &str, source: &str, color: bool) -> String {
+        let paint = |style: &str, text: &str| match color {
+            true => format!("{}{}{}", style, text, RESET),
+            false => text.to_string(),
+        };
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{}{}",
+            paint(RED, "error"),
+            paint(BOLD, &format!(": {}", self.message))
+        )
+        .unwrap();
+
+        let span = match self.span {
+            Some(span) => span,
+            None => {
+                writeln!(out, "{} {}", paint(BLUE, "-->"), source_name).unwrap();
+                return out;
+            }
+        };
+
+        // Gutter is as wide as the largest line number shown
+        let mut labels = self.labels.iter().collect::<Vec<_>>();
+        labels.sort_by_key(|label| (label.span.line, label.span.start));
+
+        let width = labels
+            .iter()
+            .map(|label| label.span.line)
+            .chain([span.line])
+            .max()
+            .unwrap_or(1)
+            .to_string()
+            .len();
+        let gutter = paint(BLUE, &format!("{} |", " ".repeat(width)));
+
+        writeln!(
+            out,
+            "{}{} {}:{}",
+            " ".repeat(width),
+            paint(BLUE, "-->"),
+            source_name,
+            span
+        )
+        .unwrap();
+        writeln!(out, "{}", gutter).unwrap();
+
+        let mut previous_line: Option<usize> = None;
+
+        for label in &labels {
+            let line = label.span.line;
+
+            if previous_line != Some(line) {
+                if previous_line.is_some_and(|prev| line > prev + 1) {
+                    writeln!(out, "{}", paint(BLUE, "...")).unwrap();
+                }
+
+                let (_, text) = source_line(source, label.span.start);
+                writeln!(
+                    out,
+                    "{} {}",
+                    paint(BLUE, &format!("{:>width$} |", line)),
+                    text
+                )
+                .unwrap();
+                previous_line = Some(line);
+            }
+
+            let (line_start, text) = source_line(source, label.span.start);
+            let start = char_boundary(
+                source,
+                label.span.start.clamp(line_start, line_start + text.len()),
+            );
+            let end = char_boundary(source, label.span.end.clamp(start, line_start + text.len()));
+
+            let offset = source[line_start..start].chars().count();
+            let length = source[start..end].chars().count().max(1);
+
+            let (style, marker) = match label.primary {
+                true => (RED, "^"),
+                false => (BLUE, "-"),
+            };
+
+            let underline = format!("{} {}", marker.repeat(length), label.message);
+            writeln!(
+                out,
+                "{} {}{}",
+                gutter,
+                " ".repeat(offset),
+                paint(style, underline.trim_end())
+            )
+            .unwrap();
+        }
+
+        if let Some(help) = &self.help {
+            writeln!(out, "{}", gutter).unwrap();
+            writeln!(
+                out,
+                "{} {}: {}",
+                paint(BLUE, &format!("{} =", " ".repeat(width))),
+                paint(BOLD, "help"),
+                help
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
+
+/// Find the line containing a byte offset, returning the offset the line starts at and its text
+fn source_line(source: &str, offset: usize) -> (usize, &str) {
+    let offset = char_boundary(source, offset);
+    let start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i);
+
+    (start, source[start..end].trim_end_matches('\r'))
+}
+
+/// Move a byte offset back to the start of the character it falls in, so slicing can't panic
+/// even if a span doesn't belong to the source
+fn char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+fn delimiter_name(open: &char) -> &'static str {
+    match open {
+        '{' => "block",
+        '[' => "list",
+        _ => "parentheses",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::Parser};
+
+    fn diagnose(input: &str) -> String {
+        let tokens = Lexer::tokenize(input.to_string()).unwrap();
+        let err = Parser::new(&tokens).parse().unwrap_err();
+
+        Diagnostic::from(&err).render("test.inty", input, false)
+    }
+
+    #[test]
+    fn render_unclosed_delimiter() {
+        assert_eq!(
+            diagnose("let x = {\n  1 + 2;\n  3\n"),
+            [
+                "error: expected `}` to close `{`",
+                " --> test.inty:3:4",
+                "  |",
+                "1 | let x = {",
+                "  |         - block opened here",
+                "...",
+                "3 |   3",
+                "  |    ^ expected `}`",
+                "  |",
+                "  = help: expected `}` to close block opened here",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn render_on_one_line() {
+        assert_eq!(
+            diagnose("1 +\n  (2 * 3"),
+            [
+                "error: expected `)` to close `(`",
+                " --> test.inty:2:9",
+                "  |",
+                "2 |   (2 * 3",
+                "  |   - parentheses opened here",
+                "  |         ^ expected `)`",
+                "  |",
+                "  = help: expected `)` to close parentheses opened here",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn render_without_labels() {
+        let err = IntyError::SyntaxError {
+            message: "bad".to_string(),
+            span: Span::new(0, 1, 1, 1),
+        };
+
+        assert_eq!(
+            Diagnostic::from(&err).render("test.inty", "x", false),
+            "error: syntax error: bad\n --> test.inty:1:1\n  |\n1 | x\n  | ^\n"
+        );
+    }
+
+    #[test]
+    fn render_non_exhaustive_match() {
+        let err = IntyError::NonExhaustiveMatch {
+            ty: "Shape".to_string(),
+            missing: vec!["Rect(w, h)".to_string(), "Empty".to_string()],
+            span: Span::new(6, 7, 1, 7),
+        };
+
+        assert_eq!(
+            Diagnostic::from(&err).render("test.inty", "match s { Circle(r) => r }", false),
+            [
+                "error: non-exhaustive match on Shape: missing Rect(w, h), Empty",
+                " --> test.inty:1:7",
+                "  |",
+                "1 | match s { Circle(r) => r }",
+                "  |       ^ Rect(w, h), Empty not covered",
+                "  |",
+                "  = help: add an arm for each missing variant, or a `_` arm",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn render_span_inside_character() {
+        // A span from a different source can fall in the middle of a character
+        let err = IntyError::DivideByZeroError {
+            span: Span::new(1, 3, 1, 2),
+        };
+
+        assert_eq!(
+            Diagnostic::from(&err).render("test.inty", "éé", false),
+            [
+                "error: cannot divide by zero",
+                " --> test.inty:1:2",
+                "  |",
+                "1 | éé",
+                "  | ^ division by zero",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use super::{pattern, Environment};
+use crate::core::*;
+use std::rc::Rc;
+
+/// Check that every match naming variants of an enum in its arms covers all of them, before any
+/// of the program runs (e.g. even a match in a function that is never called)
+pub fn check_matches(stmts: &[Stmt], env: &Environment) -> IntyResult<()> {
+    let mut program = Program::default();
+    for stmt in stmts {
+        program.stmt(stmt);
+    }
+
+    for (value, arms) in &program.matches {
+        let Some(ty) = program.resolve(arms, env) else {
+            continue;
+        };
+
+        let missing = pattern::missing_variants(&ty, arms)?;
+        if !missing.is_empty() {
+            return Err(IntyError::NonExhaustiveMatch {
+                ty: ty.ident.clone(),
+                missing,
+                span: value.span,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The enums declared and the matches found anywhere in a program
+#[derive(Default)]
+struct Program<'a> {
+    enums: Vec<Rc<EnumType>>,
+    matches: Vec<(&'a Expr, &'a [MatchArm])>,
+}
+
+impl<'a> Program<'a> {
+    /// Find the enum of the first variant named by an arm, looking at the enums declared in the
+    /// program before the ones already defined
+    fn resolve(&self, arms: &[MatchArm], env: &Environment) -> Option<Rc<EnumType>> {
+        arms.iter().find_map(|arm| {
+            let PatternKind::Variant { ident, .. } = &arm.pattern.kind else {
+                return None;
+            };
+
+            let declared = self
+                .enums
+                .iter()
+                .rev()
+                .find(|ty| ty.variants.iter().any(|variant| variant.ident == *ident));
+
+            match declared {
+                Some(ty) => Some(ty.clone()),
+                None => match env.get(ident.clone())? {
+                    Value::Variant(variant) => Some(variant.ty),
+                    Value::Constructor(ty, _) => Some(ty),
+                    _ => None,
+                },
+            }
+        })
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt) {
+        match &stmt.kind {
+            StmtKind::Expr(expr) | StmtKind::Let { expr, .. } | StmtKind::Assign { expr, .. } => {
+                self.expr(expr)
+            }
+            StmtKind::SetIndex { path, expr, .. } => {
+                path.iter().for_each(|index| self.expr(index));
+                self.expr(expr);
+            }
+            StmtKind::Delete { path, .. } => path.iter().for_each(|index| self.expr(index)),
+            StmtKind::Enum { ident, variants } => {
+                self.enums.push(Rc::new(EnumType::new(ident, variants)))
+            }
+            StmtKind::Fn { body, .. } => self.stmt(body),
+            StmtKind::While { test, body } => {
+                self.expr(test);
+                self.stmt(body);
+            }
+            StmtKind::For {
+                init,
+                test,
+                update,
+                body,
+            } => {
+                self.stmt(init);
+                self.expr(test);
+                self.stmt(update);
+                self.stmt(body);
+            }
+            StmtKind::ForIn { iter, body, .. } => {
+                self.expr(iter);
+                self.stmt(body);
+            }
+            StmtKind::Struct { .. } | StmtKind::Break | StmtKind::Continue => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &'a Expr) {
+        match &expr.kind {
+            ExprKind::Integer(_)
+            | ExprKind::Float(_)
+            | ExprKind::Str(_)
+            | ExprKind::Bool(_)
+            | ExprKind::Ident(_)
+            | ExprKind::Update { .. } => {}
+            ExprKind::Interpolate(parts) => {
+                for part in parts {
+                    if let InterpolatePart::Expr(expr) = part {
+                        self.expr(expr);
+                    }
+                }
+            }
+            ExprKind::List(items) | ExprKind::Tuple(items) => {
+                items.iter().for_each(|item| self.expr(item))
+            }
+            ExprKind::Map(entries) => {
+                for (key, value) in entries {
+                    self.expr(key);
+                    self.expr(value);
+                }
+            }
+            ExprKind::Record { fields, base, .. } => {
+                fields.iter().for_each(|(_, value)| self.expr(value));
+                base.iter().for_each(|base| self.expr(base));
+            }
+            ExprKind::Field { target, .. } | ExprKind::Spread(target) => self.expr(target),
+            ExprKind::If {
+                test,
+                branch,
+                else_branch,
+            } => {
+                self.expr(test);
+                self.stmt(branch);
+                else_branch.iter().for_each(|branch| self.stmt(branch));
+            }
+            ExprKind::Match { value, arms } => {
+                self.matches.push((value, arms));
+                self.expr(value);
+                for arm in arms {
+                    arm.guard.iter().for_each(|guard| self.expr(guard));
+                    self.stmt(&arm.body);
+                }
+            }
+            ExprKind::Block(stmts) => stmts.iter().for_each(|stmt| self.stmt(stmt)),
+            ExprKind::Lambda { body, .. } => self.stmt(body),
+            ExprKind::Range {
+                start, end, step, ..
+            } => {
+                self.expr(start);
+                self.expr(end);
+                step.iter().for_each(|step| self.expr(step));
+            }
+            ExprKind::Call { callee, args } => {
+                self.expr(callee);
+                args.iter().for_each(|arg| self.expr(arg));
+            }
+            ExprKind::Index { target, index } => {
+                self.expr(target);
+                self.expr(index);
+            }
+            ExprKind::Slice { target, start, end } => {
+                self.expr(target);
+                start.iter().chain(end).for_each(|bound| self.expr(bound));
+            }
+            ExprKind::Unary { value, .. } => self.expr(value),
+            ExprKind::Binary { lhs, rhs, .. }
+            | ExprKind::Bitwise { lhs, rhs, .. }
+            | ExprKind::Logical { lhs, rhs, .. }
+            | ExprKind::Relational { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+        }
+    }
+}
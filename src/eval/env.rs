@@ -41,4 +41,17 @@ impl Environment {
     pub fn put(&mut self, ident: String, value: Value) -> Option<Value> {
         self.table.insert(ident, value)
     }
+
+    /// Update the value in the nearest environment that contains the identifier,
+    /// returning the previous value, or `None` if the identifier is not bound
+    pub fn assign(&mut self, ident: String, value: Value) -> Option<Value> {
+        if self.has(ident.clone()) {
+            return self.put(ident, value);
+        }
+
+        match &self.parent {
+            Some(parent) => parent.borrow_mut().assign(ident, value),
+            None => None,
+        }
+    }
 }
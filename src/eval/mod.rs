@@ -1,193 +1,761 @@
+mod check;
 mod env;
+mod ops;
+mod pattern;
 
 use std::{cell::RefCell, rc::Rc};
 
-use self::env::Environment;
 use crate::core::*;
 
+pub use self::{env::Environment, ops::equal};
+
 pub struct Evaluator {
     env: Rc<RefCell<Environment>>,
+    truthiness: Truthiness,
+}
+
+/// How non-boolean values are treated by conditions, `!`, `&&` and `||`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Truthiness {
+    /// Integers are true only when positive
+    #[default]
+    Legacy,
+
+    /// Only booleans are allowed
+    Strict,
+
+    /// Numbers are true when non-zero
+    Conventional,
+}
+
+/// Signal that unwinds evaluation, either up to the nearest enclosing loop
+/// (`break` and `continue`) or all the way out of the program (errors)
+enum Interrupt {
+    Break(Span),
+    Continue(Span),
+    Error(IntyError),
+}
+
+type EvalResult<T> = Result<T, Interrupt>;
+
+impl From<IntyError> for Interrupt {
+    fn from(value: IntyError) -> Self {
+        Interrupt::Error(value)
+    }
+}
+
+impl From<std::cell::BorrowError> for Interrupt {
+    fn from(value: std::cell::BorrowError) -> Self {
+        Interrupt::Error(value.into())
+    }
+}
+
+impl From<std::cell::BorrowMutError> for Interrupt {
+    fn from(value: std::cell::BorrowMutError) -> Self {
+        Interrupt::Error(value.into())
+    }
+}
+
+impl From<Interrupt> for IntyError {
+    fn from(value: Interrupt) -> Self {
+        match value {
+            Interrupt::Break(span) => IntyError::LogicError {
+                message: "`break` outside of a loop".to_string(),
+                span,
+            },
+            Interrupt::Continue(span) => IntyError::LogicError {
+                message: "`continue` outside of a loop".to_string(),
+                span,
+            },
+            Interrupt::Error(err) => err,
+        }
+    }
 }
 
 impl Evaluator {
     pub fn new() -> Self {
         Self {
             env: Rc::new(RefCell::new(Environment::new())),
+            truthiness: Truthiness::default(),
         }
     }
 
     pub fn new_with_env(env: Rc<RefCell<Environment>>) -> Self {
-        Self { env }
+        Self {
+            env,
+            truthiness: Truthiness::default(),
+        }
+    }
+
+    /// Set how non-boolean values are converted to booleans
+    pub fn with_truthiness(mut self, truthiness: Truthiness) -> Self {
+        self.truthiness = truthiness;
+        self
+    }
+
+    /// Create an evaluator for a child scope, keeping this evaluator's options
+    fn child(&self, env: Environment) -> Self {
+        Self::new_with_env(Rc::new(RefCell::new(env))).with_truthiness(self.truthiness)
+    }
+
+    /// Set or remove an element of a list or map stored in a variable, writing back the result
+    fn update_index(
+        &self,
+        ident: &str,
+        path: &[Rc<Expr>],
+        value: Option<Value>,
+        span: Span,
+    ) -> EvalResult<()> {
+        let mut target = match self.env.try_borrow()?.get(ident.to_string()) {
+            Some(val) => val.clone(),
+            None => {
+                return Err(IntyError::UnknownIdentifier {
+                    ident: ident.to_string(),
+                    span,
+                }
+                .into())
+            }
+        };
+
+        let path = path
+            .iter()
+            .map(|index| Ok((self.eval_expr(index)?, index.span)))
+            .collect::<EvalResult<Vec<_>>>()?;
+
+        ops::set_index(&mut target, &path, value, span)?;
+        self.env.try_borrow_mut()?.assign(ident.to_string(), target);
+
+        Ok(())
+    }
+
+    /// Evaluate an expression as a condition
+    fn eval_bool(&self, expr: &Expr) -> EvalResult<bool> {
+        Ok(self
+            .eval_expr(expr)?
+            .try_parse_bool(self.truthiness, expr.span)?)
     }
 
     /// Evaluate a program's statements into a list of values
     pub fn eval(&mut self, stmts: Vec<Stmt>) -> IntyResult<Vec<Option<Value>>> {
         let mut results = vec![];
 
+        check::check_matches(&stmts, &*self.env.try_borrow()?)?;
+
         for stmt in &stmts {
-            results.push(self.eval_stmt(stmt)?);
+            results.push(self.eval_stmt(stmt).map_err(IntyError::from)?);
         }
 
         Ok(results)
     }
 
-    fn eval_stmt(&mut self, stmt: &Stmt) -> IntyResult<Option<Value>> {
-        Ok(match stmt {
-            Stmt::Expr(expr) => Some(self.eval_expr(&expr)?),
-            Stmt::If {
-                test,
-                branch: block,
-                else_branch: else_block,
-            } => {
-                if self.eval_expr(test)?.try_parse_bool()? {
-                    self.eval_stmt(block)?
-                } else if let Some(else_block) = else_block {
-                    self.eval_stmt(else_block)?
-                } else {
-                    None
+    fn eval_stmt(&self, stmt: &Stmt) -> EvalResult<Option<Value>> {
+        Ok(match &stmt.kind {
+            StmtKind::Expr(expr) => Some(self.eval_expr(expr)?),
+            StmtKind::Let { pattern, expr } => {
+                let val = self.eval_expr(expr)?;
+
+                let mut bindings = Vec::new();
+                pattern::destructure(pattern, val, &mut bindings)?;
+
+                let mut env = self.env.try_borrow_mut()?;
+                for (ident, val) in bindings {
+                    env.put(ident, val);
+                }
+                None
+            }
+            StmtKind::Assign { ident, expr } => {
+                let val = self.eval_expr(expr)?;
+                if self
+                    .env
+                    .try_borrow_mut()?
+                    .assign(ident.clone(), val)
+                    .is_none()
+                {
+                    return Err(IntyError::UnknownIdentifier {
+                        ident: ident.clone(),
+                        span: stmt.span,
+                    }
+                    .into());
                 }
+                None
             }
-            Stmt::Let { ident, expr } => {
+            StmtKind::SetIndex { ident, path, expr } => {
                 let val = self.eval_expr(expr)?;
-                self.env.try_borrow_mut()?.put(ident.clone(), val);
+                self.update_index(ident, path, Some(val), stmt.span)?;
+                None
+            }
+            StmtKind::Delete { ident, path } => {
+                self.update_index(ident, path, None, stmt.span)?;
                 None
             }
-            Stmt::Block(stmts) => {
-                // I dunno if this is just a gap in my knowledge, but the
-                // following line would need to be `mut` if we allowed re-assignment,
-                // but we don't so this is good for now. Shadowing works.
+            StmtKind::Struct { ident, fields } => {
+                let ty = Value::Struct(Rc::new(StructType {
+                    ident: ident.clone(),
+                    fields: fields.clone(),
+                }));
+                self.env.try_borrow_mut()?.put(ident.clone(), ty);
+                None
+            }
+            StmtKind::Enum { ident, variants } => {
+                let ty = Rc::new(EnumType::new(ident, variants));
+
+                // Variants holding values are constructed by calling them, the rest are values
+                let mut env = self.env.try_borrow_mut()?;
+                for (index, variant) in ty.variants.iter().enumerate() {
+                    let val = match variant.fields.is_empty() {
+                        true => Value::Variant(Variant {
+                            ty: ty.clone(),
+                            index,
+                            values: Vec::new(),
+                        }),
+                        false => Value::Constructor(ty.clone(), index),
+                    };
+                    env.put(variant.ident.clone(), val);
+                }
+                env.put(ident.clone(), Value::Enum(ty.clone()));
+                None
+            }
+            StmtKind::Fn {
+                ident,
+                params,
+                body,
+            } => {
+                let func = Value::Function(Function {
+                    ident: Some(ident.clone()),
+                    params: params.clone(),
+                    body: body.clone(),
+                    env: self.env.clone(),
+                });
+                self.env.try_borrow_mut()?.put(ident.clone(), func);
+                None
+            }
+            StmtKind::While { test, body } => {
+                while self.eval_bool(test)? {
+                    match self.eval_stmt(body) {
+                        Ok(_) | Err(Interrupt::Continue(_)) => {}
+                        Err(Interrupt::Break(_)) => break,
+                        Err(err) => return Err(err),
+                    }
+                }
+
+                None
+            }
+            StmtKind::For {
+                init,
+                test,
+                update,
+                body,
+            } => {
+                // The loop variable lives in its own scope around the body
                 let env = Environment::new_with_parent(self.env.clone());
-                let mut eval = Evaluator::new_with_env(Rc::new(RefCell::new(env)));
+                let eval = self.child(env);
 
-                if let Some(val) = stmts
-                    .iter()
-                    .map(|stmt| eval.eval_stmt(stmt))
-                    .collect::<Vec<_>>()
-                    .pop()
-                {
-                    val?
-                } else {
-                    return Err(IntyError::SyntaxError {
-                        message: "block contained no return value".to_string(),
-                    });
+                eval.eval_stmt(init)?;
+
+                while eval.eval_bool(test)? {
+                    match eval.eval_stmt(body) {
+                        Ok(_) | Err(Interrupt::Continue(_)) => {}
+                        Err(Interrupt::Break(_)) => break,
+                        Err(err) => return Err(err),
+                    }
+
+                    eval.eval_stmt(update)?;
+                }
+
+                None
+            }
+            StmtKind::ForIn { ident, iter, body } => {
+                let span = iter.span;
+                let iter = self.eval_expr(iter)?;
+                let mut results = vec![];
+
+                for item in iter.try_iter(span)? {
+                    // Each iteration gets a fresh scope holding the loop variable
+                    let mut env = Environment::new_with_parent(self.env.clone());
+                    env.put(ident.clone(), item);
+
+                    let eval = self.child(env);
+                    match eval.eval_stmt(body) {
+                        Ok(Some(val)) => results.push(val),
+                        Ok(None) | Err(Interrupt::Continue(_)) => {}
+                        Err(Interrupt::Break(_)) => break,
+                        Err(err) => return Err(err),
+                    }
                 }
+
+                Some(Value::List(results))
             }
+            StmtKind::Break => return Err(Interrupt::Break(stmt.span)),
+            StmtKind::Continue => return Err(Interrupt::Continue(stmt.span)),
         })
     }
 
     /// Recursively evaluate a single statement
-    fn eval_expr(&self, expr: &Expr) -> IntyResult<Value> {
-        Ok(match expr {
-            Expr::Integer(val) => Value::Integer(*val),
-            Expr::Ident(ident) => match self.env.try_borrow()?.get(ident.clone()) {
-                Some(val) => val.clone(),
-                None => {
-                    return Err(IntyError::UnknownIdentifier {
-                        ident: ident.clone(),
-                    })
+    fn eval_expr(&self, expr: &Expr) -> EvalResult<Value> {
+        Ok(match &expr.kind {
+            ExprKind::Integer(val) => Value::Integer(val.clone()),
+            ExprKind::Float(val) => Value::Float(*val),
+            ExprKind::Str(val) => Value::Str(val.clone()),
+            ExprKind::Interpolate(parts) => {
+                let mut string = String::new();
+
+                for part in parts {
+                    match part {
+                        InterpolatePart::Literal(val) => string.push_str(val),
+                        InterpolatePart::Expr(expr) => {
+                            string.push_str(&self.eval_expr(expr)?.to_string())
+                        }
+                    }
                 }
-            },
-            Expr::Bool(val) => Value::Bool(*val),
-            Expr::List(exprs) => Value::List(
-                exprs
-                    .into_iter()
-                    .map(|expr| self.eval_expr(expr))
-                    .collect::<IntyResult<Vec<_>>>()?,
+
+                Value::Str(string)
+            }
+            ExprKind::Tuple(items) => Value::Tuple(
+                items
+                    .iter()
+                    .map(|item| self.eval_expr(item))
+                    .collect::<EvalResult<_>>()?,
             ),
-            Expr::Unary { operator, value } => match operator {
-                UnOp::Minus => {
-                    if let Value::Integer(value) = self.eval_expr(value)? {
-                        Value::Integer(-1 * value)
-                    } else {
+            ExprKind::Map(entries) => {
+                let mut map = Map::default();
+                for (key, value) in entries {
+                    map.insert(self.eval_expr(key)?, self.eval_expr(value)?);
+                }
+
+                Value::Map(map)
+            }
+            ExprKind::Record {
+                ident,
+                fields,
+                base,
+            } => {
+                let ty = match self.env.try_borrow()?.get(ident.clone()) {
+                    Some(Value::Struct(ty)) => ty.clone(),
+                    Some(val) => {
                         return Err(IntyError::TypeError {
-                            message: "expected integer".to_string(),
-                        });
+                            message: format!("{} is not a struct", val),
+                            span: expr.span,
+                        }
+                        .into())
                     }
+                    None => {
+                        return Err(IntyError::UnknownIdentifier {
+                            ident: ident.clone(),
+                            span: expr.span,
+                        }
+                        .into())
+                    }
+                };
+
+                // Fields start out copied from the base record, if there is one
+                let mut values = match base {
+                    Some(base) => match self.eval_expr(base)? {
+                        Value::Record(record) if record.ty == ty => {
+                            record.values.into_iter().map(Some).collect()
+                        }
+                        val => {
+                            return Err(IntyError::TypeError {
+                                message: format!("{} is not a {} record", val, ty.ident),
+                                span: base.span,
+                            }
+                            .into())
+                        }
+                    },
+                    None => vec![None; ty.fields.len()],
+                };
+
+                for (field, value) in fields {
+                    let Some(index) = ty.fields.iter().position(|f| f == field) else {
+                        return Err(IntyError::UnknownField {
+                            field: field.clone(),
+                            ty: ty.ident.clone(),
+                            span: expr.span,
+                        }
+                        .into());
+                    };
+                    values[index] = Some(self.eval_expr(value)?);
+                }
+
+                let missing = ty
+                    .fields
+                    .iter()
+                    .zip(&values)
+                    .filter(|(_, value)| value.is_none())
+                    .map(|(field, _)| format!("`{}`", field))
+                    .collect::<Vec<_>>();
+
+                if !missing.is_empty() {
+                    return Err(IntyError::TypeError {
+                        message: format!("missing {} in {}", missing.join(", "), ty.ident),
+                        span: expr.span,
+                    }
+                    .into());
+                }
+
+                Value::Record(Record {
+                    ty,
+                    values: values.into_iter().flatten().collect(),
+                })
+            }
+            ExprKind::Field { target, field } => match self.eval_expr(target)? {
+                Value::Record(record) => match record.get(field) {
+                    Some(val) => val.clone(),
+                    None => {
+                        return Err(IntyError::UnknownField {
+                            field: field.clone(),
+                            ty: record.ty.ident.clone(),
+                            span: expr.span,
+                        }
+                        .into())
+                    }
+                },
+                val => {
+                    return Err(IntyError::TypeError {
+                        message: format!("{} is not a record", val),
+                        span: target.span,
+                    }
+                    .into())
                 }
-                UnOp::Plus => self.eval_expr(value)?,
-                UnOp::Negate => Value::Bool(!self.eval_expr(value)?.try_parse_bool()?),
             },
-            Expr::Binary { operator, lhs, rhs } => Value::Integer(match operator {
-                BinOp::Add => {
-                    self.eval_expr(lhs.as_ref())?.try_parse_int()?
-                        + self.eval_expr(rhs.as_ref())?.try_parse_int()?
+            ExprKind::Ident(ident) => match self.env.try_borrow()?.get(ident.clone()) {
+                Some(val) => val.clone(),
+                None => {
+                    return Err(IntyError::UnknownIdentifier {
+                        ident: ident.clone(),
+                        span: expr.span,
+                    }
+                    .into())
                 }
-                BinOp::Sub => {
-                    self.eval_expr(lhs.as_ref())?.try_parse_int()?
-                        - self.eval_expr(rhs.as_ref())?.try_parse_int()?
+            },
+            ExprKind::Bool(val) => Value::Bool(*val),
+            ExprKind::List(exprs) => {
+                let mut items = Vec::new();
+                for expr in exprs {
+                    match &expr.kind {
+                        ExprKind::Spread(value) => {
+                            items.extend(ops::to_list(self.eval_expr(value)?, value.span)?)
+                        }
+                        _ => items.push(self.eval_expr(expr)?),
+                    }
                 }
-                BinOp::Mul => {
-                    self.eval_expr(lhs.as_ref())?.try_parse_int()?
-                        * self.eval_expr(rhs.as_ref())?.try_parse_int()?
+                Value::List(items)
+            }
+            ExprKind::Spread(_) => unreachable!("spreads are only parsed in list literals"),
+            ExprKind::If {
+                test,
+                branch,
+                else_branch,
+            } => {
+                let branch = if self.eval_bool(test)? {
+                    Some(branch)
+                } else {
+                    else_branch.as_ref()
+                };
+
+                match branch {
+                    Some(branch) => self.eval_stmt(branch)?.unwrap_or(Value::Unit),
+                    None => Value::Unit,
                 }
-                BinOp::Div => {
-                    let left = self.eval_expr(lhs.as_ref())?.try_parse_int()?;
-                    let right = self.eval_expr(rhs.as_ref())?.try_parse_int()?;
+            }
+            ExprKind::Match { value, arms } => {
+                let subject = self.eval_expr(value)?;
 
-                    match right {
-                        0 => return Err(IntyError::DivideByZeroError),
-                        _ => left / right,
+                // A match on an enum has to handle every variant, not just the one it was given
+                if let Value::Variant(variant) = &subject {
+                    let missing = pattern::missing_variants(&variant.ty, arms)?;
+                    if !missing.is_empty() {
+                        return Err(IntyError::NonExhaustiveMatch {
+                            ty: variant.ty.ident.clone(),
+                            missing,
+                            span: value.span,
+                        }
+                        .into());
                     }
                 }
-                BinOp::Pow => {
-                    let base = self.eval_expr(lhs.as_ref())?.try_parse_int()?;
-                    let pow = self.eval_expr(rhs.as_ref())?.try_parse_int()?;
 
-                    if pow < 0 {
-                        return Err(IntyError::LogicError {
-                            message: "power must be a non-negative integer".to_string(),
-                        });
+                for arm in arms {
+                    let mut bindings = Vec::new();
+                    match pattern::destructure(&arm.pattern, subject.clone(), &mut bindings) {
+                        Ok(()) => {}
+                        Err(IntyError::PatternMismatch { .. }) => continue,
+                        Err(err) => return Err(err.into()),
+                    }
+
+                    // Each arm gets its own scope for the names bound by its pattern
+                    let mut env = Environment::new_with_parent(self.env.clone());
+                    for (ident, val) in bindings {
+                        env.put(ident, val);
+                    }
+                    let eval = self.child(env);
+
+                    if let Some(guard) = &arm.guard {
+                        if !eval.eval_bool(guard)? {
+                            continue;
+                        }
                     }
 
-                    base.pow(pow as u32)
+                    return Ok(eval.eval_stmt(&arm.body)?.unwrap_or(Value::Unit));
                 }
-            }),
-            Expr::Logical { operator, lhs, rhs } => Value::Bool({
-                let left = self.eval_expr(lhs.as_ref())?.try_parse_bool()?;
-                let right = self.eval_expr(rhs.as_ref())?.try_parse_bool()?;
 
-                match operator {
-                    LogOp::And => left && right,
-                    LogOp::Or => left || right,
+                return Err(IntyError::PatternMismatch {
+                    message: format!("no arm matches {}", subject.to_nested_string()),
+                    span: value.span,
+                }
+                .into());
+            }
+            ExprKind::Block(stmts) => {
+                // Re-assignment walks up to the parent environment, so the block
+                // only needs its own scope for new `let` bindings (shadowing)
+                let env = Environment::new_with_parent(self.env.clone());
+                let eval = self.child(env);
+
+                let mut value = None;
+                for stmt in stmts {
+                    value = eval.eval_stmt(stmt)?;
                 }
+
+                // Blocks ending in a statement without a value (e.g. `let`) produce unit
+                value.unwrap_or(Value::Unit)
+            }
+            ExprKind::Lambda { params, body } => Value::Function(Function {
+                ident: None,
+                params: params.clone(),
+                body: body.clone(),
+                env: self.env.clone(),
             }),
-            Expr::Relational { operator, lhs, rhs } => Value::Bool({
-                let left = self.eval_expr(lhs.as_ref())?;
-                let right = self.eval_expr(rhs.as_ref())?;
+            ExprKind::Range {
+                start,
+                end,
+                step,
+                inclusive,
+            } => {
+                let first = self.eval_expr(start)?;
+                let last = self.eval_expr(end)?;
+                let (step, step_span) = match step {
+                    Some(step) => (self.eval_expr(step)?, step.span),
+                    None => (Value::Integer(1.into()), expr.span),
+                };
 
-                match (left, right) {
-                    (Value::Integer(lhs), Value::Integer(rhs)) => match operator {
-                        RelOp::Eq => lhs == rhs,
-                        RelOp::Ne => lhs != rhs,
-                        RelOp::Gt => lhs > rhs,
-                        RelOp::Lt => lhs < rhs,
-                        RelOp::Gte => lhs >= rhs,
-                        RelOp::Lte => lhs <= rhs,
-                    },
-                    (Value::Bool(lhs), Value::Bool(rhs)) => match operator {
-                        RelOp::Eq => lhs == rhs,
-                        RelOp::Ne => lhs != rhs,
-                        _ => {
-                            return Err(IntyError::SyntaxError {
-                                message: "operation not permitted".to_string(),
-                            })
+                match Range::new(
+                    first.try_parse_int(start.span)?.clone(),
+                    last.try_parse_int(end.span)?.clone(),
+                    step.try_parse_int(step_span)?.clone(),
+                    *inclusive,
+                ) {
+                    Some(range) => Value::Range(range),
+                    None => {
+                        return Err(IntyError::LogicError {
+                            message: "range step must not be zero".to_string(),
+                            span: step_span,
                         }
+                        .into())
+                    }
+                }
+            }
+            ExprKind::Index { target, index } => {
+                let value = self.eval_expr(target)?;
+                let key = self.eval_expr(index)?;
+
+                ops::index(value, &key, target.span, index.span)?
+            }
+            ExprKind::Slice { target, start, end } => {
+                let value = self.eval_expr(target)?;
+                let start = match start {
+                    Some(start) => Some((self.eval_expr(start)?, start.span)),
+                    None => None,
+                };
+                let end = match end {
+                    Some(end) => Some((self.eval_expr(end)?, end.span)),
+                    None => None,
+                };
+
+                ops::slice(
+                    value,
+                    match &start {
+                        Some((val, span)) => Some((val.try_parse_int(*span)?, *span)),
+                        None => None,
                     },
-                    (Value::List(lhs), Value::List(rhs)) => match operator {
-                        RelOp::Eq => lhs == rhs,
-                        RelOp::Ne => lhs != rhs,
-                        _ => {
-                            return Err(IntyError::SyntaxError {
-                                message: "operation not permitted".to_string(),
-                            })
-                        }
+                    match &end {
+                        Some((val, span)) => Some((val.try_parse_int(*span)?, *span)),
+                        None => None,
                     },
+                    target.span,
+                )?
+            }
+            ExprKind::Call { callee, args } => {
+                let span = callee.span;
+                let callee = self.eval_expr(callee)?;
+
+                if let Value::Constructor(ty, index) = callee {
+                    let fields = &ty.variants[index].fields;
+                    if args.len() != fields.len() {
+                        return Err(IntyError::ArityError {
+                            expected: fields.len(),
+                            found: args.len(),
+                            span: expr.span,
+                        }
+                        .into());
+                    }
+
+                    let values = args
+                        .iter()
+                        .map(|arg| self.eval_expr(arg))
+                        .collect::<EvalResult<_>>()?;
+                    return Ok(Value::Variant(Variant { ty, index, values }));
+                }
+
+                let func = callee.try_parse_function(span)?;
+
+                if args.len() != func.params.len() {
+                    return Err(IntyError::ArityError {
+                        expected: func.params.len(),
+                        found: args.len(),
+                        span: expr.span,
+                    }
+                    .into());
+                }
+
+                // Arguments are bound in a new scope on top of the captured environment
+                let mut env = Environment::new_with_parent(func.env.clone());
+                for (param, arg) in func.params.iter().zip(args) {
+                    env.put(param.clone(), self.eval_expr(arg)?);
+                }
+
+                // Loop signals must not escape the function they were raised in
+                self.child(env)
+                    .eval_stmt(&func.body)
+                    .map_err(IntyError::from)?
+                    .unwrap_or(Value::Unit)
+            }
+            ExprKind::Update {
+                operator,
+                ident,
+                prefix,
+            } => {
+                let old = match self.env.try_borrow()?.get(ident.clone()) {
+                    Some(val) => val.clone(),
+                    None => {
+                        return Err(IntyError::UnknownIdentifier {
+                            ident: ident.clone(),
+                            span: expr.span,
+                        }
+                        .into())
+                    }
+                };
+
+                let operator = match operator {
+                    UpdOp::Inc => BinOp::Add,
+                    UpdOp::Dec => BinOp::Sub,
+                };
+                let new = ops::arithmetic(
+                    &operator,
+                    old.clone(),
+                    Value::Integer(1.into()),
+                    expr.span,
+                    expr.span,
+                    expr.span,
+                )?;
+
+                self.env
+                    .try_borrow_mut()?
+                    .assign(ident.clone(), new.clone());
+
+                if *prefix {
+                    new
+                } else {
+                    old
+                }
+            }
+            ExprKind::Unary { operator, value } => match operator {
+                UnOp::Minus => match self.eval_expr(value)? {
+                    Value::Integer(value) => Value::Integer(-value),
+                    Value::Float(value) => Value::Float(-value),
                     _ => {
-                        return Err(IntyError::SyntaxError {
-                            message: "comparison not permitted".to_string(),
-                        })
+                        return Err(IntyError::TypeError {
+                            message: "expected number".to_string(),
+                            span: value.span,
+                        }
+                        .into())
                     }
+                },
+                UnOp::Plus => self.eval_expr(value)?,
+                UnOp::Negate => Value::Bool(!self.eval_bool(value)?),
+                UnOp::BitNot => {
+                    Value::Integer(!self.eval_expr(value)?.try_parse_int(value.span)?.clone())
                 }
+            },
+            ExprKind::Binary { operator, lhs, rhs } => {
+                let left = self.eval_expr(lhs.as_ref())?;
+                let right = self.eval_expr(rhs.as_ref())?;
+
+                match (operator, left, right) {
+                    // Only strings can be added to strings, anything else has to be interpolated
+                    (BinOp::Add, Value::Str(left), Value::Str(right)) => Value::Str(left + &right),
+                    (BinOp::Add, left @ Value::Str(_), right)
+                    | (BinOp::Add, left, right @ Value::Str(_)) => {
+                        return Err(IntyError::TypeError {
+                            message: format!(
+                                "cannot add {} to {}",
+                                right.type_name(),
+                                left.type_name()
+                            ),
+                            span: expr.span,
+                        }
+                        .into())
+                    }
+                    (
+                        BinOp::Add,
+                        left @ (Value::List(_) | Value::Range(_)),
+                        right @ (Value::List(_) | Value::Range(_)),
+                    ) => Value::List(
+                        [
+                            ops::to_list(left, lhs.span)?,
+                            ops::to_list(right, rhs.span)?,
+                        ]
+                        .concat(),
+                    ),
+                    (
+                        BinOp::Mul,
+                        items @ (Value::List(_) | Value::Range(_)),
+                        Value::Integer(count),
+                    ) => ops::repeat(ops::to_list(items, lhs.span)?, &count, expr.span, rhs.span)?,
+                    (
+                        BinOp::Mul,
+                        Value::Integer(count),
+                        items @ (Value::List(_) | Value::Range(_)),
+                    ) => ops::repeat(ops::to_list(items, rhs.span)?, &count, expr.span, lhs.span)?,
+                    (operator, left, right) => {
+                        ops::arithmetic(operator, left, right, expr.span, lhs.span, rhs.span)?
+                    }
+                }
+            }
+            ExprKind::Bitwise { operator, lhs, rhs } => {
+                let left = self.eval_expr(lhs.as_ref())?;
+                let right = self.eval_expr(rhs.as_ref())?;
+
+                Value::Integer(ops::bitwise(
+                    operator,
+                    left.try_parse_int(lhs.span)?,
+                    right.try_parse_int(rhs.span)?,
+                    expr.span,
+                    rhs.span,
+                )?)
+            }
+            ExprKind::Logical { operator, lhs, rhs } => Value::Bool({
+                let left = self.eval_bool(lhs)?;
+
+                // The right side is only evaluated if it can still change the result
+                match (operator, left) {
+                    (LogOp::And, false) => false,
+                    (LogOp::Or, true) => true,
+                    _ => self.eval_bool(rhs)?,
+                }
+            }),
+            ExprKind::Relational { operator, lhs, rhs } => Value::Bool({
+                let left = self.eval_expr(lhs.as_ref())?;
+                let right = self.eval_expr(rhs.as_ref())?;
+
+                ops::relational(operator, &left, &right, expr.span)?
             }),
         })
     }
@@ -198,14 +766,26 @@ mod tests {
     use super::*;
     use std::rc::Rc;
 
+    /// Build an expression without a location
+    fn expr(kind: ExprKind) -> Expr {
+        Expr::new(kind, Span::default())
+    }
+
+    /// Build a statement without a location
+    fn stmt(kind: StmtKind) -> Stmt {
+        Stmt::new(kind, Span::default())
+    }
+
     #[test]
     fn single_number() {
         let value = Evaluator::new()
-            .eval(vec![Stmt::Expr(Expr::Integer(100))])
+            .eval(vec![stmt(StmtKind::Expr(expr(ExprKind::Integer(
+                100.into(),
+            ))))])
             .unwrap();
 
         assert_eq!(
-            Value::Integer(100),
+            Value::Integer(100.into()),
             *value.last().unwrap().as_ref().unwrap()
         );
     }
@@ -213,14 +793,17 @@ mod tests {
     #[test]
     fn basic_addition() {
         let value = Evaluator::new()
-            .eval(vec![Stmt::Expr(Expr::Binary {
+            .eval(vec![stmt(StmtKind::Expr(expr(ExprKind::Binary {
                 operator: BinOp::Add,
-                lhs: Rc::new(Expr::Integer(1)),
-                rhs: Rc::new(Expr::Integer(2)),
-            })])
+                lhs: Rc::new(expr(ExprKind::Integer(1.into()))),
+                rhs: Rc::new(expr(ExprKind::Integer(2.into()))),
+            })))])
             .unwrap();
 
-        assert_eq!(Value::Integer(3), *value.last().unwrap().as_ref().unwrap());
+        assert_eq!(
+            Value::Integer(3.into()),
+            *value.last().unwrap().as_ref().unwrap()
+        );
     }
 
     #[test]
@@ -228,20 +811,20 @@ mod tests {
         let mut evaler = Evaluator::new();
 
         evaler
-            .eval(vec![Stmt::Let {
-                ident: "foo".into(),
-                expr: Expr::Integer(42),
-            }])
+            .eval(vec![stmt(StmtKind::Let {
+                pattern: Pattern::ident("foo".into(), Span::default()),
+                expr: expr(ExprKind::Integer(42.into())),
+            })])
             .unwrap();
 
         assert_eq!(
-            42,
+            &Int::from(42),
             evaler
                 .env
                 .borrow()
                 .get("foo".into())
                 .unwrap()
-                .try_parse_int()
+                .try_parse_int(Span::default())
                 .unwrap()
         );
     }
@@ -252,14 +835,17 @@ mod tests {
 
         let value = evaler
             .eval(vec![
-                Stmt::Let {
-                    ident: "foo".into(),
-                    expr: Expr::Integer(42),
-                },
-                Stmt::Expr(Expr::Ident("foo".to_string())),
+                stmt(StmtKind::Let {
+                    pattern: Pattern::ident("foo".into(), Span::default()),
+                    expr: expr(ExprKind::Integer(42.into())),
+                }),
+                stmt(StmtKind::Expr(expr(ExprKind::Ident("foo".to_string())))),
             ])
             .unwrap();
 
-        assert_eq!(Value::Integer(42), *value.last().unwrap().as_ref().unwrap());
+        assert_eq!(
+            Value::Integer(42.into()),
+            *value.last().unwrap().as_ref().unwrap()
+        );
     }
 }
@@ -0,0 +1,482 @@
+use crate::core::*;
+use std::{cmp::Ordering, fmt::Display};
+
+/// Most elements a list can hold before it would be larger than can ever be allocated
+const MAX_LIST_LEN: usize = isize::MAX as usize / std::mem::size_of::<Value>();
+
+/// Apply an arithmetic operator to two numbers. Integers stay integers (including division,
+/// which truncates), but if either side is a float or the operator is `/.`, both sides are
+/// promoted to floats. Results too large to represent raise an overflow error instead.
+pub fn arithmetic(
+    operator: &BinOp,
+    left: Value,
+    right: Value,
+    span: Span,
+    lhs_span: Span,
+    rhs_span: Span,
+) -> IntyResult<Value> {
+    let overflow = |left: &dyn Display, right: &dyn Display| IntyError::OverflowError {
+        operation: format!("{} {} {}", left, operator, right),
+        span,
+    };
+
+    Ok(match (left, right) {
+        (Value::Integer(left), Value::Integer(right)) if *operator != BinOp::FloatDiv => {
+            Value::Integer(match operator {
+                BinOp::Add => left + right,
+                BinOp::Sub => left - right,
+                BinOp::Mul => left * right,
+                BinOp::Div | BinOp::FloatDiv => match left.checked_div(&right) {
+                    Some(val) => val,
+                    None => return Err(IntyError::DivideByZeroError { span }),
+                },
+                BinOp::FloorDiv => match left.checked_div_floor(&right) {
+                    Some(val) => val,
+                    None => return Err(IntyError::DivideByZeroError { span }),
+                },
+                BinOp::Rem => match left.checked_rem(&right) {
+                    Some(val) => val,
+                    None => return Err(IntyError::DivideByZeroError { span }),
+                },
+                BinOp::Mod => match left.checked_rem_euclid(&right) {
+                    Some(val) => val,
+                    None => return Err(IntyError::DivideByZeroError { span }),
+                },
+                BinOp::Pow => {
+                    if right.is_negative() {
+                        return Err(IntyError::LogicError {
+                            message: "power must be a non-negative integer".to_string(),
+                            span: rhs_span,
+                        });
+                    }
+
+                    match left.checked_pow(&right) {
+                        Some(val) => val,
+                        None => return Err(overflow(&left, &right)),
+                    }
+                }
+            })
+        }
+        (left, right) => {
+            let left = left.try_parse_float(lhs_span)?;
+            let right = right.try_parse_float(rhs_span)?;
+
+            let result = match operator {
+                BinOp::Add => left + right,
+                BinOp::Sub => left - right,
+                BinOp::Mul => left * right,
+                BinOp::Div | BinOp::FloatDiv | BinOp::FloorDiv | BinOp::Rem | BinOp::Mod
+                    if right == 0.0 =>
+                {
+                    return Err(IntyError::DivideByZeroError { span })
+                }
+                BinOp::Div | BinOp::FloatDiv => left / right,
+                BinOp::FloorDiv => (left / right).floor(),
+                BinOp::Rem => left % right,
+                BinOp::Mod => left.rem_euclid(right),
+                BinOp::Pow => left.powf(right),
+            };
+
+            // Floats are kept finite, so e.g. `1e308 * 10` is reported rather than becoming `inf`
+            if result.is_infinite() {
+                return Err(overflow(&Value::Float(left), &Value::Float(right)));
+            }
+
+            Value::Float(result)
+        }
+    })
+}
+
+/// Apply a bitwise operator to two integers, treating negative numbers as two's complement
+pub fn bitwise(
+    operator: &BitOp,
+    left: &Int,
+    right: &Int,
+    span: Span,
+    rhs_span: Span,
+) -> IntyResult<Int> {
+    if matches!(operator, BitOp::Shl | BitOp::Shr) && right.is_negative() {
+        return Err(IntyError::LogicError {
+            message: "shift amount must be a non-negative integer".to_string(),
+            span: rhs_span,
+        });
+    }
+
+    Ok(match operator {
+        BitOp::And => left.clone() & right.clone(),
+        BitOp::Or => left.clone() | right.clone(),
+        BitOp::Xor => left.clone() ^ right.clone(),
+        BitOp::Shl => match left.checked_shl(right) {
+            Some(val) => val,
+            None => {
+                return Err(IntyError::OverflowError {
+                    operation: format!("{} {} {}", left, operator, right),
+                    span,
+                })
+            }
+        },
+        BitOp::Shr => left.shr(right),
+    })
+}
+
+/// Compare two values with a relational operator. Values of different types cannot be
+/// compared, except for integers and floats.
+pub fn relational(operator: &RelOp, left: &Value, right: &Value, span: Span) -> IntyResult<bool> {
+    if *operator == RelOp::In {
+        return contains(right, left, span);
+    }
+
+    let ordering = compare(operator, left, right, span)?;
+
+    Ok(match operator {
+        RelOp::Eq => ordering == Some(Ordering::Equal),
+        RelOp::Ne => ordering != Some(Ordering::Equal),
+        RelOp::Gt => ordering == Some(Ordering::Greater),
+        RelOp::Lt => ordering == Some(Ordering::Less),
+        RelOp::Gte => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        RelOp::Lte => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        RelOp::In => unreachable!(),
+    })
+}
+
+/// Order two values, or `None` if they are not equal and have no order between them (e.g.
+/// `NaN`). Lists are ordered lexicographically.
+fn compare(
+    operator: &RelOp,
+    left: &Value,
+    right: &Value,
+    span: Span,
+) -> IntyResult<Option<Ordering>> {
+    let equality = matches!(operator, RelOp::Eq | RelOp::Ne);
+
+    Ok(match (left, right) {
+        (Value::Integer(left), Value::Integer(right)) => Some(left.cmp(right)),
+        (Value::Integer(_) | Value::Float(_), Value::Integer(_) | Value::Float(_)) => left
+            .try_parse_float(span)?
+            .partial_cmp(&right.try_parse_float(span)?),
+        (Value::Str(left), Value::Str(right)) => Some(left.cmp(right)),
+        (Value::List(left), Value::List(right)) | (Value::Tuple(left), Value::Tuple(right)) => {
+            for (left, right) in left.iter().zip(right) {
+                // Elements of different types can never be equal, but they can't be ordered
+                if equality && !comparable(left, right) {
+                    return Ok(None);
+                }
+
+                match compare(operator, left, right, span)? {
+                    Some(Ordering::Equal) => {}
+                    ordering => return Ok(ordering),
+                }
+            }
+
+            Some(left.len().cmp(&right.len()))
+        }
+        (Value::Range(left), Value::Range(right)) => Some(left.cmp(right)),
+        // Only as many values as the list has, plus one to tell if the range is longer, matter
+        (Value::Range(range), Value::List(items)) => compare(
+            operator,
+            &Value::List(
+                range
+                    .iter()
+                    .take(items.len() + 1)
+                    .map(Value::Integer)
+                    .collect(),
+            ),
+            right,
+            span,
+        )?,
+        (Value::List(items), Value::Range(range)) => compare(
+            operator,
+            left,
+            &Value::List(
+                range
+                    .iter()
+                    .take(items.len() + 1)
+                    .map(Value::Integer)
+                    .collect(),
+            ),
+            span,
+        )?,
+        // Keys have to match exactly to be found, but values are compared like list elements
+        (Value::Map(left), Value::Map(right)) if equality => (left.len() == right.len()
+            && left.iter().all(|(key, value)| {
+                right
+                    .get(key)
+                    .is_some_and(|other| equal(value, other, span))
+            }))
+        .then_some(Ordering::Equal),
+        (
+            Value::Unit
+            | Value::Bool(_)
+            | Value::Struct(_)
+            | Value::Record(_)
+            | Value::Enum(_)
+            | Value::Variant(_)
+            | Value::Constructor(..)
+            | Value::Function(_),
+            _,
+        ) if equality && comparable(left, right) => (left == right).then_some(Ordering::Equal),
+        _ if comparable(left, right) => {
+            return Err(IntyError::TypeError {
+                message: format!("cannot use `{}` on {} values", operator, left.type_name()),
+                span,
+            })
+        }
+        _ => {
+            return Err(IntyError::TypeError {
+                message: format!(
+                    "cannot compare {} with {}",
+                    left.type_name(),
+                    right.type_name()
+                ),
+                span,
+            })
+        }
+    })
+}
+
+/// Check if two values are of types that can be compared with each other
+fn comparable(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Integer(_) | Value::Float(_), Value::Integer(_) | Value::Float(_)) => true,
+        (Value::List(_) | Value::Range(_), Value::List(_) | Value::Range(_)) => true,
+        (left, right) => std::mem::discriminant(left) == std::mem::discriminant(right),
+    }
+}
+
+/// Check if two values are equal, where values of different types are never equal
+pub fn equal(left: &Value, right: &Value, span: Span) -> bool {
+    comparable(left, right)
+        && matches!(
+            compare(&RelOp::Eq, left, right, span),
+            Ok(Some(Ordering::Equal))
+        )
+}
+
+/// Check if a list or range contains a value, a map contains a key, or a string contains a
+/// substring
+fn contains(collection: &Value, item: &Value, span: Span) -> IntyResult<bool> {
+    match (collection, item) {
+        (Value::List(items), item) => Ok(items.iter().any(|other| equal(item, other, span))),
+        (Value::Range(range), Value::Integer(item)) => Ok(range.contains(item)),
+        (Value::Range(range), Value::Float(item)) => {
+            Ok(Int::from_f64(*item).is_some_and(|item| range.contains(&item)))
+        }
+        (Value::Range(_), _) => Ok(false),
+        (Value::Map(map), item) => Ok(map.contains_key(item)),
+        (Value::Str(val), Value::Str(item)) => Ok(val.contains(item.as_str())),
+        _ => Err(IntyError::TypeError {
+            message: format!(
+                "cannot check if {} is in {}",
+                item.type_name(),
+                collection.type_name()
+            ),
+            span,
+        }),
+    }
+}
+
+/// Repeat the elements of a list a number of times
+pub fn repeat(items: Vec<Value>, count: &Int, span: Span, count_span: Span) -> IntyResult<Value> {
+    if count.is_negative() {
+        return Err(IntyError::LogicError {
+            message: "repeat count must be a non-negative integer".to_string(),
+            span: count_span,
+        });
+    }
+
+    match count
+        .to_usize()
+        .filter(|count| items.len().saturating_mul(*count) <= MAX_LIST_LEN)
+    {
+        Some(count) => Ok(Value::List(
+            items
+                .iter()
+                .cycle()
+                .take(items.len() * count)
+                .cloned()
+                .collect(),
+        )),
+        None => Err(IntyError::OverflowError {
+            operation: format!("{} * {}", Value::List(items), count),
+            span,
+        }),
+    }
+}
+
+/// Get the elements of a list, turning a range into one if needed
+pub fn to_list(value: Value, span: Span) -> IntyResult<Vec<Value>> {
+    match value {
+        Value::List(items) => Ok(items),
+        Value::Range(range) => match range.len().to_usize() {
+            Some(len) if len <= MAX_LIST_LEN => Ok(range.iter().map(Value::Integer).collect()),
+            _ => Err(IntyError::OverflowError {
+                operation: range.to_string(),
+                span,
+            }),
+        },
+        _ => Err(IntyError::TypeError {
+            message: format!("{} is not a list", value),
+            span,
+        }),
+    }
+}
+
+/// Get a single element of a list, tuple or range, or character of a string, counting from
+/// the end if the index is negative. Maps are indexed by key instead.
+pub fn index(
+    target: Value,
+    index: &Value,
+    target_span: Span,
+    index_span: Span,
+) -> IntyResult<Value> {
+    if let Value::Map(map) = &target {
+        return match map.get(index) {
+            Some(val) => Ok(val.clone()),
+            None => Err(key_not_found(index, index_span)),
+        };
+    }
+
+    let length = length(&target, target_span)?;
+    let position = position(index.try_parse_int(index_span)?, &length, false, index_span)?;
+
+    // Positions within the bounds of a list or string always fit in a `usize`
+    Ok(match target {
+        Value::List(items) | Value::Tuple(items) => {
+            items[position.to_usize().expect("in bounds")].clone()
+        }
+        Value::Str(val) => Value::Str(
+            val.chars()
+                .nth(position.to_usize().expect("in bounds"))
+                .expect("in bounds")
+                .to_string(),
+        ),
+        Value::Range(range) => Value::Integer(range.nth(&position)),
+        _ => unreachable!(),
+    })
+}
+
+/// Get the elements of a list, tuple, range or string between two indices, defaulting to the
+/// start and end. Bounds are counted from the end if negative.
+pub fn slice(
+    target: Value,
+    start: Option<(&Int, Span)>,
+    end: Option<(&Int, Span)>,
+    target_span: Span,
+) -> IntyResult<Value> {
+    let length = length(&target, target_span)?;
+
+    let start = match start {
+        Some((index, span)) => position(index, &length, true, span)?,
+        None => 0.into(),
+    };
+    let end = match end {
+        Some((index, span)) => position(index, &length, true, span)?,
+        None => length,
+    };
+
+    if let Value::Range(range) = target {
+        return Ok(Value::Range(range.slice(&start, &end)));
+    }
+
+    // Bounds that cross over select nothing rather than being an error
+    let start = start.to_usize().expect("in bounds");
+    let end = end.to_usize().expect("in bounds").max(start);
+
+    Ok(match target {
+        Value::List(items) => Value::List(items[start..end].to_vec()),
+        Value::Tuple(items) => Value::Tuple(items[start..end].to_vec()),
+        Value::Str(val) => Value::Str(val.chars().skip(start).take(end - start).collect()),
+        _ => unreachable!(),
+    })
+}
+
+/// Get the number of elements in an indexable value
+fn length(target: &Value, span: Span) -> IntyResult<Int> {
+    match target {
+        Value::List(items) | Value::Tuple(items) => Ok(items.len().into()),
+        Value::Str(val) => Ok(val.chars().count().into()),
+        Value::Range(range) => Ok(range.len()),
+        _ => Err(IntyError::TypeError {
+            message: format!("{} is not indexable", target),
+            span,
+        }),
+    }
+}
+
+/// Resolve a possibly negative index into a position within `length` elements. Slice bounds
+/// may also point just past the last element.
+fn position(index: &Int, length: &Int, is_bound: bool, span: Span) -> IntyResult<Int> {
+    let position = match index.is_negative() {
+        true => length.clone() + index.clone(),
+        false => index.clone(),
+    };
+
+    match !position.is_negative() && (position < *length || (is_bound && position == *length)) {
+        true => Ok(position),
+        false => Err(IntyError::IndexOutOfBounds {
+            index: index.clone(),
+            length: length.clone(),
+            span,
+        }),
+    }
+}
+
+/// Set (or remove, if there is no value) the element of a list or map found by following a
+/// path of indices from `target`, where all but the last must already exist
+pub fn set_index(
+    target: &mut Value,
+    path: &[(Value, Span)],
+    value: Option<Value>,
+    target_span: Span,
+) -> IntyResult<()> {
+    let ((index, index_span), rest) = path.split_first().expect("path is never empty");
+
+    match &mut *target {
+        Value::Map(map) => match (rest.is_empty(), value) {
+            (true, Some(value)) => map.insert(index.clone(), value),
+            (true, None) => {
+                if map.remove(index).is_none() {
+                    return Err(key_not_found(index, *index_span));
+                }
+            }
+            (false, value) => match map.get_mut(index) {
+                Some(element) => set_index(element, rest, value, *index_span)?,
+                None => return Err(key_not_found(index, *index_span)),
+            },
+        },
+        Value::List(items) => {
+            let length = items.len().into();
+            let position = position(
+                index.try_parse_int(*index_span)?,
+                &length,
+                false,
+                *index_span,
+            )?
+            .to_usize()
+            .expect("in bounds");
+
+            match (rest.is_empty(), value) {
+                (true, Some(value)) => items[position] = value,
+                (true, None) => {
+                    items.remove(position);
+                }
+                (false, value) => set_index(&mut items[position], rest, value, *index_span)?,
+            }
+        }
+        _ => {
+            return Err(IntyError::TypeError {
+                message: format!("{} cannot be changed by index", target),
+                span: target_span,
+            })
+        }
+    }
+
+    Ok(())
+}
+
+fn key_not_found(key: &Value, span: Span) -> IntyError {
+    IntyError::KeyNotFound {
+        key: key.to_nested_string(),
+        span,
+    }
+}
@@ -0,0 +1,130 @@
+use super::ops;
+use crate::core::*;
+
+/// Match a value against a pattern, collecting the value bound to each name. Nothing should be
+/// bound unless the whole pattern matches.
+pub fn destructure(
+    pattern: &Pattern,
+    value: Value,
+    bindings: &mut Vec<(String, Value)>,
+) -> IntyResult<()> {
+    let mismatch = |expected: String, value: &Value| IntyError::PatternMismatch {
+        message: format!("expected {}, found {}", expected, value.to_nested_string()),
+        span: pattern.span,
+    };
+
+    match (&pattern.kind, value) {
+        (PatternKind::Ident(ident), value) => bindings.push((ident.clone(), value)),
+        (PatternKind::Wildcard, _) => {}
+        (PatternKind::Literal(literal), value) => {
+            if !ops::equal(literal, &value, pattern.span) {
+                return Err(mismatch(literal.to_nested_string(), &value));
+            }
+        }
+        (PatternKind::Variant { ident, fields }, Value::Variant(variant))
+            if variant.ident() == ident && variant.values.len() == fields.len() =>
+        {
+            for (pattern, value) in fields.iter().zip(variant.values) {
+                destructure(pattern, value, bindings)?;
+            }
+        }
+        (PatternKind::Variant { ident, fields }, value) => {
+            let expected = match fields.len() {
+                0 => format!("`{}`", ident),
+                count => format!("`{}` holding {}", ident, plural(count, "value")),
+            };
+            return Err(mismatch(expected, &value));
+        }
+        (PatternKind::Tuple(patterns), Value::Tuple(values)) if patterns.len() == values.len() => {
+            for (pattern, value) in patterns.iter().zip(values) {
+                destructure(pattern, value, bindings)?;
+            }
+        }
+        (PatternKind::Tuple(patterns), value) => {
+            return Err(mismatch(
+                format!("a tuple of {}", plural(patterns.len(), "element")),
+                &value,
+            ))
+        }
+        (PatternKind::List { items, rest }, Value::List(mut values))
+            if values.len() == items.len() || (rest.is_some() && values.len() > items.len()) =>
+        {
+            let remaining = values.split_off(items.len());
+
+            for (pattern, value) in items.iter().zip(values) {
+                destructure(pattern, value, bindings)?;
+            }
+
+            if let Some(rest) = rest {
+                destructure(rest, Value::List(remaining), bindings)?;
+            }
+        }
+        (PatternKind::List { items, rest }, value) => {
+            let expected = match rest {
+                Some(_) => format!("a list of at least {}", plural(items.len(), "element")),
+                None => format!("a list of {}", plural(items.len(), "element")),
+            };
+            return Err(mismatch(expected, &value));
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the variants of an enum that no arm of a match is sure to match. Only arms without a
+/// guard, whose pattern is a name, `_` or a variant holding only names and `_`, cover anything.
+pub fn missing_variants(ty: &EnumType, arms: &[MatchArm]) -> IntyResult<Vec<String>> {
+    let mut covered = vec![false; ty.variants.len()];
+
+    for arm in arms {
+        let unguarded = arm.guard.is_none();
+
+        match &arm.pattern.kind {
+            PatternKind::Variant { ident, fields } => {
+                let index = match ty.variants.iter().position(|v| v.ident == *ident) {
+                    Some(index) => index,
+                    None => {
+                        return Err(IntyError::TypeError {
+                            message: format!("{} is not a variant of {}", ident, ty.ident),
+                            span: arm.pattern.span,
+                        })
+                    }
+                };
+
+                let expected = ty.variants[index].fields.len();
+                if fields.len() != expected {
+                    return Err(IntyError::TypeError {
+                        message: format!(
+                            "{} holds {}, but the pattern has {}",
+                            ident,
+                            plural(expected, "value"),
+                            fields.len()
+                        ),
+                        span: arm.pattern.span,
+                    });
+                }
+
+                if unguarded && fields.iter().all(Pattern::is_irrefutable) {
+                    covered[index] = true;
+                }
+            }
+            _ if unguarded && arm.pattern.is_irrefutable() => return Ok(Vec::new()),
+            _ => {}
+        }
+    }
+
+    Ok(ty
+        .variants
+        .iter()
+        .zip(covered)
+        .filter(|(_, covered)| !covered)
+        .map(|(variant, _)| variant.to_string())
+        .collect())
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", count, noun),
+    }
+}
@@ -1,98 +1,451 @@
 use crate::core::*;
+use std::{iter::Peekable, str::CharIndices};
 
-pub struct Lexer {}
+pub struct Lexer<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
 
-impl Lexer {
+    /// Byte offset in the input where lexing started, which `chars` counts from
+    base: usize,
+    line: usize,
+    column: usize,
+
+    /// The last token read, used to tell `x--` apart from `5--3`
+    previous: Option<Token>,
+}
+
+impl<'a> Lexer<'a> {
     /// Parse a string into a vector of valid tokens
-    pub fn tokenize(input: String) -> IntyResult<Vec<Token>> {
+    pub fn tokenize(input: String) -> IntyResult<Vec<SpannedToken>> {
+        Self::tokenize_from(&input, (0, 1))
+    }
+
+    /// Parse the input from a byte offset on, given the line it starts, so the spans of the
+    /// tokens point into the whole input (e.g. the lines of a REPL session so far)
+    pub fn tokenize_from(
+        input: &str,
+        (start, line): (usize, usize),
+    ) -> IntyResult<Vec<SpannedToken>> {
+        let mut lexer = Lexer {
+            input,
+            chars: input[start..].char_indices().peekable(),
+            base: start,
+            line,
+            column: 1,
+            previous: None,
+        };
+
         let mut tokens = vec![];
+        while let Some(token) = lexer.next_token()? {
+            lexer.previous = Some(token.token.clone());
+            tokens.push(token);
+        }
 
-        let mut chars = input.chars().peekable();
-        while let Some(ch) = chars.next() {
-            tokens.push(match ch {
-                ' ' | '\t' | '\r' | '\n' => continue,
-                '0'..='9' => {
-                    let mut number = ch.to_string();
-                    while let Some('0'..='9') = chars.peek() {
-                        number.push(chars.next().expect("we are peeking ahead so this is safe"));
-                    }
-                    Token::Integer(number.parse()?)
+        Ok(tokens)
+    }
+
+    /// Parse the next token along with its span, skipping any leading whitespace
+    fn next_token(&mut self) -> IntyResult<Option<SpannedToken>> {
+        while let Some(' ' | '\t' | '\r' | '\n') = self.peek() {
+            self.next();
+        }
+
+        let (start, line, column) = (self.offset(), self.line, self.column);
+        let Some(ch) = self.next() else {
+            return Ok(None);
+        };
+
+        let token = match ch {
+            '0'..='9' => {
+                let mut number = ch.to_string();
+                self.digits(&mut number);
+
+                let mut float = false;
+
+                // Only treat `.` as a decimal point if a digit follows, so it can't swallow a
+                // following operator (e.g. a range `1..2`)
+                if self.peek() == Some('.')
+                    && self.peek_nth(1).is_some_and(|ch| ch.is_ascii_digit())
+                {
+                    float = true;
+                    number.push(self.next().expect("we are peeking ahead so this is safe"));
+                    self.digits(&mut number);
                 }
-                'a'..='z' => {
-                    let mut ident = ch.to_string();
-                    while let Some('a'..='z' | 'A'..='Z' | '0'..='9' | '_') = chars.peek() {
-                        ident.push(chars.next().expect("we are peeking ahead so this is safe"));
-                    }
 
-                    match Token::map_keyword(ident.as_ref()) {
-                        Some(keyword) => keyword,
-                        None => Token::Ident(ident),
+                if let Some('e' | 'E') = self.peek() {
+                    let sign = matches!(self.peek_nth(1), Some('+' | '-'));
+                    let digit = self.peek_nth(if sign { 2 } else { 1 });
+
+                    if digit.is_some_and(|ch| ch.is_ascii_digit()) {
+                        float = true;
+                        number.push(self.next().expect("we are peeking ahead so this is safe"));
+                        if sign {
+                            number.push(self.next().expect("we are peeking ahead so this is safe"));
+                        }
+                        self.digits(&mut number);
                     }
                 }
-                '+' => Token::Plus,
-                '-' => Token::Hyphen,
-                '*' => Token::Star,
-                '/' => Token::Divide,
-                '^' => Token::Caret,
-                '=' => {
-                    if let Some('=') = chars.peek() {
-                        chars.next();
-                        Token::RelEq
-                    } else {
-                        Token::Equal
+
+                if float {
+                    match number.parse::<f64>() {
+                        Ok(value) if value.is_finite() => Token::Float(value),
+                        _ => {
+                            return Err(IntyError::TokenParsingError {
+                                text: number,
+                                message: "number is too large to be a float".to_string(),
+                                span: self.span_from(start, line, column),
+                            })
+                        }
                     }
-                }
-                '!' => {
-                    if let Some('=') = chars.peek() {
-                        chars.next();
-                        Token::RelNe
-                    } else {
-                        Token::Bang
+                } else {
+                    match number.parse() {
+                        Ok(value) => Token::Integer(value),
+                        Err(_) => {
+                            return Err(IntyError::TokenParsingError {
+                                text: number,
+                                message: "not a valid integer".to_string(),
+                                span: self.span_from(start, line, column),
+                            })
+                        }
                     }
                 }
-                '<' => {
-                    if let Some('=') = chars.peek() {
-                        chars.next();
-                        Token::RelLte
-                    } else {
-                        Token::RelLt
-                    }
+            }
+            '"' => self.string(start, line, column)?,
+            'a'..='z' | 'A'..='Z' | '_' => {
+                let mut ident = ch.to_string();
+                while let Some('a'..='z' | 'A'..='Z' | '0'..='9' | '_') = self.peek() {
+                    ident.push(self.next().expect("we are peeking ahead so this is safe"));
+                }
+
+                match Token::map_keyword(ident.as_ref()) {
+                    Some(keyword) => keyword,
+                    None => Token::Ident(ident),
+                }
+            }
+            '+' => match self.peek() {
+                Some('=') => {
+                    self.next();
+                    Token::PlusEqual
                 }
-                '>' => {
-                    if let Some('=') = chars.peek() {
-                        chars.next();
-                        Token::RelGte
-                    } else {
-                        Token::RelGt
+                Some('+') if self.is_update() => {
+                    self.next();
+                    Token::PlusPlus
+                }
+                _ => Token::Plus,
+            },
+            '-' => match self.peek() {
+                Some('=') => {
+                    self.next();
+                    Token::HyphenEqual
+                }
+                Some('-') if self.is_update() => {
+                    self.next();
+                    Token::HyphenHyphen
+                }
+                _ => Token::Hyphen,
+            },
+            '*' => {
+                if let Some('=') = self.peek() {
+                    self.next();
+                    Token::StarEqual
+                } else {
+                    Token::Star
+                }
+            }
+            '/' => match self.peek() {
+                Some('=') => {
+                    self.next();
+                    Token::DivideEqual
+                }
+                Some('.') => {
+                    self.next();
+                    Token::DivideDot
+                }
+                Some('/') => {
+                    self.next();
+                    Token::DivideDivide
+                }
+                _ => Token::Divide,
+            },
+            '^' => match self.peek() {
+                Some('=') => {
+                    self.next();
+                    Token::CaretEqual
+                }
+                Some('^') => {
+                    self.next();
+                    Token::CaretCaret
+                }
+                _ => Token::Caret,
+            },
+            '=' => match self.peek() {
+                Some('=') => {
+                    self.next();
+                    Token::RelEq
+                }
+                Some('>') => {
+                    self.next();
+                    Token::FatArrow
+                }
+                _ => Token::Equal,
+            },
+            '!' => {
+                if let Some('=') = self.peek() {
+                    self.next();
+                    Token::RelNe
+                } else {
+                    Token::Bang
+                }
+            }
+            '<' => match self.peek() {
+                Some('=') => {
+                    self.next();
+                    Token::RelLte
+                }
+                Some('<') => {
+                    self.next();
+                    Token::ShiftLeft
+                }
+                _ => Token::RelLt,
+            },
+            '>' => match self.peek() {
+                Some('=') => {
+                    self.next();
+                    Token::RelGte
+                }
+                Some('>') => {
+                    self.next();
+                    Token::ShiftRight
+                }
+                _ => Token::RelGt,
+            },
+            '&' => {
+                if let Some('&') = self.peek() {
+                    self.next();
+                    Token::And
+                } else {
+                    Token::Ampersand
+                }
+            }
+            '|' => {
+                if let Some('|') = self.peek() {
+                    self.next();
+                    Token::Or
+                } else {
+                    Token::Pipe
+                }
+            }
+            '~' => Token::Tilde,
+            '.' if self.peek() == Some('.') => {
+                self.next();
+
+                match self.peek() {
+                    Some('=') => {
+                        self.next();
+                        Token::DotDotEqual
+                    }
+                    Some('.') => {
+                        self.next();
+                        Token::DotDotDot
                     }
+                    _ => Token::DotDot,
                 }
-                '&' => {
-                    if let Some('&') = chars.next() {
-                        Token::And
-                    } else {
-                        return Err(IntyError::UnexpectedChar { character: ch });
+            }
+            '.' => Token::Dot,
+            '%' => Token::Percent,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            ';' => Token::Semicolon,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            _ => {
+                return Err(IntyError::UnexpectedChar {
+                    character: ch,
+                    span: self.span_from(start, line, column),
+                })
+            }
+        };
+
+        Ok(Some(SpannedToken::new(
+            token,
+            self.span_from(start, line, column),
+        )))
+    }
+
+    /// Parse the rest of a string literal after its opening quote, including any expressions
+    /// embedded between braces (e.g. `"total: {x + 1}"`)
+    fn string(&mut self, start: usize, line: usize, column: usize) -> IntyResult<Token> {
+        let mut string = String::new();
+        let mut parts = vec![];
+
+        loop {
+            let position = (self.offset(), self.line, self.column);
+
+            match self.next() {
+                Some('"') if parts.is_empty() => return Ok(Token::Str(string)),
+                Some('"') => {
+                    if !string.is_empty() {
+                        parts.push(StrPart::Literal(string));
                     }
+                    return Ok(Token::Interpolated(parts));
                 }
-                '|' => {
-                    if let Some('|') = chars.next() {
-                        Token::Or
-                    } else {
-                        return Err(IntyError::UnexpectedChar { character: ch });
+                Some('{') => {
+                    if !string.is_empty() {
+                        parts.push(StrPart::Literal(std::mem::take(&mut string)));
                     }
+                    parts.push(StrPart::Tokens(self.interpolation(position)?));
                 }
-                '(' => Token::LeftParen,
-                ')' => Token::RightParen,
-                '[' => Token::LeftBracket,
-                ']' => Token::RightBracket,
-                '{' => Token::LeftBrace,
-                '}' => Token::RightBrace,
-                ';' => Token::Semicolon,
-                ',' => Token::Comma,
-                _ => return Err(IntyError::UnexpectedChar { character: ch }),
-            });
+                Some('\\') => string.push(self.escape(position)?),
+                Some(ch) => string.push(ch),
+                None => {
+                    return Err(IntyError::SyntaxError {
+                        message: "unterminated string".to_string(),
+                        span: self.span_from(start, line, column),
+                    })
+                }
+            }
         }
+    }
 
-        Ok(tokens)
+    /// Tokenize an expression embedded in a string after its opening brace, up to the matching closing brace
+    fn interpolation(
+        &mut self,
+        (start, line, column): (usize, usize, usize),
+    ) -> IntyResult<Vec<SpannedToken>> {
+        let mut tokens = vec![];
+        let mut depth = 0;
+
+        loop {
+            // Running out of input leaves the braces open, even if it was inside a string that
+            // looked like it started within them (e.g. the closing quote of `"a{"`)
+            let token = match self.next_token() {
+                Ok(Some(token)) => token,
+                Err(err) if self.peek().is_some() => return Err(err),
+                _ => {
+                    return Err(IntyError::SyntaxError {
+                        message: "unterminated string interpolation".to_string(),
+                        span: self.span_from(start, line, column),
+                    })
+                }
+            };
+
+            match token.token {
+                Token::LeftBrace => depth += 1,
+                Token::RightBrace if depth == 0 => return Ok(tokens),
+                Token::RightBrace => depth -= 1,
+                _ => {}
+            }
+
+            tokens.push(token);
+        }
+    }
+
+    /// Parse an escape sequence after its backslash (e.g. `\n` or `\u{1F600}`)
+    fn escape(&mut self, (start, line, column): (usize, usize, usize)) -> IntyResult<char> {
+        let ch = match self.next() {
+            Some('n') => Some('\n'),
+            Some('t') => Some('\t'),
+            Some('"') => Some('"'),
+            Some('\\') => Some('\\'),
+            Some('{') => Some('{'),
+            Some('}') => Some('}'),
+            Some('u') if self.peek() == Some('{') => {
+                self.next();
+
+                let mut digits = String::new();
+                while let Some(ch) = self.peek().filter(|ch| ch.is_ascii_hexdigit()) {
+                    digits.push(ch);
+                    self.next();
+                }
+
+                match self.next() {
+                    Some('}') => u32::from_str_radix(&digits, 16)
+                        .ok()
+                        .and_then(char::from_u32),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        ch.ok_or_else(|| IntyError::TokenParsingError {
+            text: self.input[start..self.offset()].to_string(),
+            message: "invalid escape sequence".to_string(),
+            span: self.span_from(start, line, column),
+        })
+    }
+
+    /// Consume the next character, keeping track of the current line and column
+    fn next(&mut self) -> Option<char> {
+        let (_, ch) = self.chars.next()?;
+
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(ch)
+    }
+
+    /// Get the next character without consuming it
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, ch)| *ch)
+    }
+
+    /// Check if a doubled `+` or `-` updates a variable rather than being two signs (e.g. `5--3`).
+    /// After a variable it has to end the operand (e.g. `x--`, but not `x--3`), and otherwise it
+    /// has to directly precede one (e.g. `--x`).
+    fn is_update(&self) -> bool {
+        let mut rest = self.chars.clone().skip(1).map(|(_, ch)| ch);
+
+        match self.previous {
+            Some(Token::Ident(_)) => !rest
+                .find(|ch| !ch.is_whitespace())
+                .is_some_and(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '(' | '[')),
+            Some(
+                Token::Integer(_)
+                | Token::Float(_)
+                | Token::Str(_)
+                | Token::Interpolated(_)
+                | Token::True
+                | Token::False
+                | Token::RightParen
+                | Token::RightBracket,
+            ) => false,
+            _ => rest
+                .next()
+                .is_some_and(|ch| ch.is_ascii_alphabetic() || ch == '_'),
+        }
+    }
+
+    /// Get the character `n` places after the next one without consuming anything
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.clone().nth(n).map(|(_, ch)| ch)
+    }
+
+    /// Consume a run of digits onto the end of a number
+    fn digits(&mut self, number: &mut String) {
+        while let Some('0'..='9') = self.peek() {
+            number.push(self.next().expect("we are peeking ahead so this is safe"));
+        }
+    }
+
+    /// Byte offset of the next character
+    fn offset(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.input.len(), |(offset, _)| self.base + offset)
+    }
+
+    /// Span from a starting position up to the current position
+    fn span_from(&mut self, start: usize, line: usize, column: usize) -> Span {
+        Span::new(start, self.offset(), line, column)
     }
 }
 
@@ -100,51 +453,345 @@ impl Lexer {
 mod tests {
     use super::*;
 
+    /// Tokenize the input, discarding spans
+    fn tokens(input: &str) -> IntyResult<Vec<Token>> {
+        Ok(Lexer::tokenize(input.into())?
+            .into_iter()
+            .map(|token| token.token)
+            .collect())
+    }
+
     #[test]
     fn tokenize() {
-        let tokens = Lexer::tokenize("1 + 2 - 3 * 4 / 5".into()).unwrap();
+        let tokens = tokens("1 + 2 - 3 * 4 / 5").unwrap();
 
         assert_eq!(
             tokens,
             vec![
-                Token::Integer(1),
+                Token::Integer(1.into()),
                 Token::Plus,
-                Token::Integer(2),
+                Token::Integer(2.into()),
                 Token::Hyphen,
-                Token::Integer(3),
+                Token::Integer(3.into()),
                 Token::Star,
-                Token::Integer(4),
+                Token::Integer(4.into()),
                 Token::Divide,
-                Token::Integer(5),
+                Token::Integer(5.into()),
             ]
         );
     }
 
     #[test]
     fn tokenize_paren() {
-        let tokens = Lexer::tokenize("1 + (2 - 3) * 4 / 5".into()).unwrap();
+        let tokens = tokens("1 + (2 - 3) * 4 / 5").unwrap();
 
         assert_eq!(
             tokens,
             vec![
-                Token::Integer(1),
+                Token::Integer(1.into()),
                 Token::Plus,
                 Token::LeftParen,
-                Token::Integer(2),
+                Token::Integer(2.into()),
                 Token::Hyphen,
-                Token::Integer(3),
+                Token::Integer(3.into()),
                 Token::RightParen,
                 Token::Star,
-                Token::Integer(4),
+                Token::Integer(4.into()),
+                Token::Divide,
+                Token::Integer(5.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_assignment_operators() {
+        let tokens = tokens("x += 1; y -= 2; z *= 3; w /= 4; v ^= 5; x++; --y").unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("x".into()),
+                Token::PlusEqual,
+                Token::Integer(1.into()),
+                Token::Semicolon,
+                Token::Ident("y".into()),
+                Token::HyphenEqual,
+                Token::Integer(2.into()),
+                Token::Semicolon,
+                Token::Ident("z".into()),
+                Token::StarEqual,
+                Token::Integer(3.into()),
+                Token::Semicolon,
+                Token::Ident("w".into()),
+                Token::DivideEqual,
+                Token::Integer(4.into()),
+                Token::Semicolon,
+                Token::Ident("v".into()),
+                Token::CaretEqual,
+                Token::Integer(5.into()),
+                Token::Semicolon,
+                Token::Ident("x".into()),
+                Token::PlusPlus,
+                Token::Semicolon,
+                Token::HyphenHyphen,
+                Token::Ident("y".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_doubled_signs() {
+        // Only a variable right before or after makes these an increment or decrement
+        assert_eq!(
+            tokens("5--3 (x)++1 x-- - --x x--3").unwrap(),
+            vec![
+                Token::Integer(5.into()),
+                Token::Hyphen,
+                Token::Hyphen,
+                Token::Integer(3.into()),
+                Token::LeftParen,
+                Token::Ident("x".into()),
+                Token::RightParen,
+                Token::Plus,
+                Token::Plus,
+                Token::Integer(1.into()),
+                Token::Ident("x".into()),
+                Token::HyphenHyphen,
+                Token::Hyphen,
+                Token::HyphenHyphen,
+                Token::Ident("x".into()),
+                Token::Ident("x".into()),
+                Token::Hyphen,
+                Token::Hyphen,
+                Token::Integer(3.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_spans() {
+        let tokens = Lexer::tokenize("let x = 12;\n  x >= 3".into()).unwrap();
+
+        assert_eq!(
+            tokens.iter().map(|token| token.span).collect::<Vec<_>>(),
+            vec![
+                Span::new(0, 3, 1, 1),
+                Span::new(4, 5, 1, 5),
+                Span::new(6, 7, 1, 7),
+                Span::new(8, 10, 1, 9),
+                Span::new(10, 11, 1, 11),
+                Span::new(14, 15, 2, 3),
+                Span::new(16, 18, 2, 5),
+                Span::new(19, 20, 2, 8),
+            ]
+        );
+
+        // Spans start from where lexing started in the input
+        let tokens = Lexer::tokenize_from("let x = 12;\nx >= 3", (12, 2)).unwrap();
+        assert_eq!(
+            tokens.iter().map(|token| token.span).collect::<Vec<_>>(),
+            vec![
+                Span::new(12, 13, 2, 1),
+                Span::new(14, 16, 2, 3),
+                Span::new(17, 18, 2, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_string() {
+        assert_eq!(
+            tokens(r#""hi there" + "a\n\t\"b\"\\" "\u{48}\u{1F600}" """#).unwrap(),
+            vec![
+                Token::Str("hi there".into()),
+                Token::Plus,
+                Token::Str("a\n\t\"b\"\\".into()),
+                Token::Str("H\u{1F600}".into()),
+                Token::Str("".into()),
+            ]
+        );
+
+        assert!(matches!(
+            tokens(r#""abc"#),
+            Err(IntyError::SyntaxError { span, .. }) if span == Span::new(0, 4, 1, 1)
+        ));
+
+        [r"\q", r"\u{110000}", r"\u{zz}", r"\u41"]
+            .into_iter()
+            .for_each(|escape| {
+                assert!(
+                    matches!(
+                        tokens(&format!("\"{}\"", escape)),
+                        Err(IntyError::TokenParsingError { .. })
+                    ),
+                    "escape = {}",
+                    escape
+                )
+            });
+    }
+
+    #[test]
+    fn tokenize_interpolated_string() {
+        assert_eq!(
+            Lexer::tokenize(r#""a {x + 1}{f("}")} \{b\}""#.into()).unwrap(),
+            vec![SpannedToken::new(
+                Token::Interpolated(vec![
+                    StrPart::Literal("a ".into()),
+                    StrPart::Tokens(vec![
+                        SpannedToken::new(Token::Ident("x".into()), Span::new(4, 5, 1, 5)),
+                        SpannedToken::new(Token::Plus, Span::new(6, 7, 1, 7)),
+                        SpannedToken::new(Token::Integer(1.into()), Span::new(8, 9, 1, 9)),
+                    ]),
+                    StrPart::Tokens(vec![
+                        SpannedToken::new(Token::Ident("f".into()), Span::new(11, 12, 1, 12)),
+                        SpannedToken::new(Token::LeftParen, Span::new(12, 13, 1, 13)),
+                        SpannedToken::new(Token::Str("}".into()), Span::new(13, 16, 1, 14)),
+                        SpannedToken::new(Token::RightParen, Span::new(16, 17, 1, 17)),
+                    ]),
+                    StrPart::Literal(" {b}".into()),
+                ]),
+                Span::new(0, 25, 1, 1)
+            )]
+        );
+
+        assert!(matches!(
+            tokens(r#""a {x + 1""#),
+            Err(IntyError::SyntaxError { .. })
+        ));
+    }
+
+    #[test]
+    fn tokenize_division_operators() {
+        assert_eq!(
+            tokens("1 / 2 // 3 % 4 mod 5 /. 6").unwrap(),
+            vec![
+                Token::Integer(1.into()),
                 Token::Divide,
-                Token::Integer(5),
+                Token::Integer(2.into()),
+                Token::DivideDivide,
+                Token::Integer(3.into()),
+                Token::Percent,
+                Token::Integer(4.into()),
+                Token::Mod,
+                Token::Integer(5.into()),
+                Token::DivideDot,
+                Token::Integer(6.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_bitwise_operators() {
+        assert_eq!(
+            tokens("a & b | c ^^ ~d << e >> f && g || h ^ i").unwrap(),
+            vec![
+                Token::Ident("a".into()),
+                Token::Ampersand,
+                Token::Ident("b".into()),
+                Token::Pipe,
+                Token::Ident("c".into()),
+                Token::CaretCaret,
+                Token::Tilde,
+                Token::Ident("d".into()),
+                Token::ShiftLeft,
+                Token::Ident("e".into()),
+                Token::ShiftRight,
+                Token::Ident("f".into()),
+                Token::And,
+                Token::Ident("g".into()),
+                Token::Or,
+                Token::Ident("h".into()),
+                Token::Caret,
+                Token::Ident("i".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_ranges() {
+        assert_eq!(
+            tokens("0..10 1..=x step 2 1.5..2 ...rest _").unwrap(),
+            vec![
+                Token::Integer(0.into()),
+                Token::DotDot,
+                Token::Integer(10.into()),
+                Token::Integer(1.into()),
+                Token::DotDotEqual,
+                Token::Ident("x".into()),
+                Token::Step,
+                Token::Integer(2.into()),
+                Token::Float(1.5),
+                Token::DotDot,
+                Token::Integer(2.into()),
+                Token::DotDotDot,
+                Token::Ident("rest".into()),
+                Token::Ident("_".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_match() {
+        assert_eq!(
+            tokens("enum match x { _ => 1 } a == b = c").unwrap(),
+            vec![
+                Token::Enum,
+                Token::Match,
+                Token::Ident("x".into()),
+                Token::LeftBrace,
+                Token::Ident("_".into()),
+                Token::FatArrow,
+                Token::Integer(1.into()),
+                Token::RightBrace,
+                Token::Ident("a".into()),
+                Token::RelEq,
+                Token::Ident("b".into()),
+                Token::Equal,
+                Token::Ident("c".into()),
             ]
         );
     }
 
+    #[test]
+    fn tokenize_float() {
+        assert_eq!(
+            tokens("2.75 1e-9 2.5E+3 1e10 7 /. 2").unwrap(),
+            vec![
+                Token::Float(2.75),
+                Token::Float(1e-9),
+                Token::Float(2500.0),
+                Token::Float(1e10),
+                Token::Integer(7.into()),
+                Token::DivideDot,
+                Token::Integer(2.into()),
+            ]
+        );
+
+        // Without digits after them, `e` and `.` are not part of the number
+        assert_eq!(
+            tokens("1e").unwrap(),
+            vec![Token::Integer(1.into()), Token::Ident("e".into())]
+        );
+        assert_eq!(
+            tokens("1.").unwrap(),
+            vec![Token::Integer(1.into()), Token::Dot]
+        );
+    }
+
     #[test]
     fn tokenize_error() {
-        let tokens = Lexer::tokenize("?".into());
-        assert!(tokens.is_err());
+        let tokens = tokens("1 ?");
+        assert!(matches!(
+            tokens,
+            Err(IntyError::UnexpectedChar {
+                character: '?',
+                span: Span {
+                    start: 2,
+                    end: 3,
+                    line: 1,
+                    column: 3
+                }
+            })
+        ));
     }
 }
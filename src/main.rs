@@ -2,12 +2,14 @@
 
 mod args;
 mod core;
+mod diagnostic;
 mod eval;
 mod lexer;
 mod parser;
 
 use crate::core::*;
 use clap::Parser as _;
+use diagnostic::Diagnostic;
 use eval::Evaluator;
 use lexer::Lexer;
 use parser::Parser;
@@ -15,34 +17,49 @@ use rustyline::{error::ReadlineError, DefaultEditor};
 
 fn main() -> IntyResult<()> {
     let args = args::Args::parse();
+    let color = args.color.enabled();
+    let truthiness = args.truthiness();
 
     match args.command {
         args::Command::Run { file } => {
-            let mut eval = Evaluator::new();
-            let input = std::fs::read_to_string(file)?;
-            let values = process_string(&mut eval, input, args.debug)?;
-            print_output(&values);
+            let mut eval = Evaluator::new().with_truthiness(truthiness);
+            let input = std::fs::read_to_string(&file)?;
+
+            if !execute(&mut eval, &file, &input, (0, 1), args.debug, color) {
+                std::process::exit(1);
+            }
         }
 
         args::Command::Eval { expr } => {
-            let mut eval = Evaluator::new();
-            let values = process_string(&mut eval, expr, args.debug)?;
-            print_output(&values);
+            let mut eval = Evaluator::new().with_truthiness(truthiness);
+
+            if !execute(&mut eval, "<eval>", &expr, (0, 1), args.debug, color) {
+                std::process::exit(1);
+            }
         }
 
         args::Command::Repl => {
             let mut rl = DefaultEditor::new()?;
-            let mut eval = Evaluator::new();
+            let mut eval = Evaluator::new().with_truthiness(truthiness);
+
+            // Functions may be called on a later line than they were defined on, so errors are
+            // rendered against every line of the session rather than just the current one
+            let mut session = Session::default();
 
             loop {
                 match rl.readline("> ") {
                     Ok(line) => {
                         rl.add_history_entry(line.as_str())?;
 
-                        match process_string(&mut eval, line, args.debug) {
-                            Ok(values) => print_output(&values),
-                            Err(err) => println!("{}", err),
-                        }
+                        let start = session.push_line(&line);
+                        execute(
+                            &mut eval,
+                            "<repl>",
+                            &session.source,
+                            start,
+                            args.debug,
+                            color,
+                        );
                     }
                     Err(ReadlineError::Eof | ReadlineError::Interrupted) => {
                         println!("inty session ended");
@@ -60,84 +77,154 @@ fn main() -> IntyResult<()> {
     Ok(())
 }
 
-fn process_string(
+/// Every line of input in a REPL session, counted as they are added so that adding one doesn't
+/// have to look back over the whole session
+#[derive(Default)]
+struct Session {
+    source: String,
+    lines: usize,
+}
+
+impl Session {
+    /// Add a line of input to the source, returning its byte offset and line number
+    fn push_line(&mut self, line: &str) -> (usize, usize) {
+        let start = (self.source.len(), self.lines + 1);
+        self.source.push_str(line);
+        self.source.push('\n');
+        self.lines += line.matches('\n').count() + 1;
+        start
+    }
+}
+
+/// Evaluate the source from a byte offset and line on, and print the results, or every error that
+/// occurred. Returns whether it succeeded
+fn execute(
     eval: &mut Evaluator,
-    input: String,
+    source_name: &str,
+    source: &str,
+    start: (usize, usize),
     debug: bool,
-) -> IntyResult<Vec<Option<Value>>> {
+    color: bool,
+) -> bool {
+    let result = parse_string(source, start, debug)
+        .and_then(|stmts| eval.eval(stmts).map_err(|err| vec![err]));
+
+    match result {
+        Ok(values) => {
+            print_output(&values);
+            true
+        }
+        Err(errors) => {
+            errors
+                .iter()
+                .for_each(|err| print_error(source_name, source, err, color));
+            false
+        }
+    }
+}
+
+/// Tokenize and parse the source from a byte offset and line on, collecting all syntax errors
+/// rather than just the first
+fn parse_string(
+    source: &str,
+    start: (usize, usize),
+    debug: bool,
+) -> Result<Vec<Stmt>, Vec<IntyError>> {
     // Tokenize input
-    let tokens = Lexer::tokenize(input)?;
+    let tokens = Lexer::tokenize_from(source, start).map_err(|err| vec![err])?;
 
     if debug {
         dbg!(&tokens);
     }
 
     // Parse tokens into a list of statements
-    let stmts = Parser::new(&tokens).parse()?;
+    let (stmts, errors) = Parser::new(&tokens).parse_with_recovery();
 
     if debug {
         dbg!(&stmts);
     }
 
+    match errors.is_empty() {
+        true => Ok(stmts),
+        false => Err(errors),
+    }
+}
+
+fn process_string(
+    eval: &mut Evaluator,
+    input: String,
+    debug: bool,
+) -> IntyResult<Vec<Option<Value>>> {
+    let stmts = parse_string(&input, (0, 1), debug).map_err(|mut errors| errors.remove(0))?;
+
     // Evaluate the parsed statements
     eval.eval(stmts)
 }
 
-fn print_output(values: &Vec<Option<Value>>) {
-    values.iter().for_each(|v| {
-        if let Some(v) = v {
-            println!("{}", v);
-        }
-    });
+fn print_output(values: &[Option<Value>]) {
+    values
+        .iter()
+        .flatten()
+        .filter(|v| **v != Value::Unit)
+        .for_each(|v| println!("{}", v));
+}
+
+/// Print an error along with the part of the source code that caused it
+fn print_error(source_name: &str, source: &str, err: &IntyError, color: bool) {
+    eprint!(
+        "{}",
+        Diagnostic::from(err).render(source_name, source, color)
+    );
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::eval::Truthiness;
 
     #[test]
     fn expression_evaluation() {
         [
-            ("1", Value::Integer(1)),
-            ("1 + 2 + 3", Value::Integer(6)),
-            ("1 + 2", Value::Integer(3)),
-            ("5 - 2", Value::Integer(3)),
-            ("-1", Value::Integer(-1)),
-            ("-5 - 2", Value::Integer(-7)),
-            ("4 + 4 + 4", Value::Integer(12)),
-            ("6 - 2 + 5", Value::Integer(9)),
-            ("10 - 2 + 3", Value::Integer(11)),
-            ("-2 + (-5)", Value::Integer(-7)),
-            ("2 * 3 + 4", Value::Integer(10)),
-            ("2 + 3 * 4", Value::Integer(14)),
-            ("2 + 3 * 5", Value::Integer(17)),
-            ("(2 + 3) * (4 - 1)", Value::Integer(15)),
-            ("(2 + 3) * 4", Value::Integer(20)),
-            ("(4 + 2) * 3", Value::Integer(18)),
-            ("(9 + 1) * (5 - 2)", Value::Integer(30)),
-            ("1 * 2 * 3 * 4", Value::Integer(24)),
-            ("1 * 2", Value::Integer(2)),
-            ("3 * 3 * 3", Value::Integer(27)),
-            ("4 * 5 - 3", Value::Integer(17)),
-            ("7 * 2", Value::Integer(14)),
-            ("10 / 2", Value::Integer(5)),
-            ("10 / 5", Value::Integer(2)),
-            ("10 * 10 / 5", Value::Integer(20)),
-            ("1 * 4 * 5 / 10 / 2 * 10", Value::Integer(10)),
-            ("3 ^ 0", Value::Integer(1)),
-            ("3 ^ 1", Value::Integer(3)),
-            ("3 ^ 2", Value::Integer(9)),
-            ("3 ^ 3", Value::Integer(27)),
-            ("-3 ^ 2", Value::Integer(-9)),
-            ("-3 ^ 3", Value::Integer(-27)),
-            ("(-3) ^ 2", Value::Integer(9)),
-            ("(-3) ^ 3", Value::Integer(-27)),
-            ("2 ^ 3 + 4", Value::Integer(12)),
-            ("3 ^ 2 + 4", Value::Integer(13)),
-            ("(-3) ^ 2 + 4", Value::Integer(13)),
-            ("2 ^ 3 * 4 + 4", Value::Integer(36)),
-            ("3 ^ 2 * 4 + 4", Value::Integer(40)),
-            ("(-3) ^ 2 * 4 + 4", Value::Integer(40)),
+            ("1", Value::Integer(1.into())),
+            ("1 + 2 + 3", Value::Integer(6.into())),
+            ("1 + 2", Value::Integer(3.into())),
+            ("5 - 2", Value::Integer(3.into())),
+            ("-1", Value::Integer((-1).into())),
+            ("-5 - 2", Value::Integer((-7).into())),
+            ("4 + 4 + 4", Value::Integer(12.into())),
+            ("6 - 2 + 5", Value::Integer(9.into())),
+            ("10 - 2 + 3", Value::Integer(11.into())),
+            ("-2 + (-5)", Value::Integer((-7).into())),
+            ("2 * 3 + 4", Value::Integer(10.into())),
+            ("2 + 3 * 4", Value::Integer(14.into())),
+            ("2 + 3 * 5", Value::Integer(17.into())),
+            ("(2 + 3) * (4 - 1)", Value::Integer(15.into())),
+            ("(2 + 3) * 4", Value::Integer(20.into())),
+            ("(4 + 2) * 3", Value::Integer(18.into())),
+            ("(9 + 1) * (5 - 2)", Value::Integer(30.into())),
+            ("1 * 2 * 3 * 4", Value::Integer(24.into())),
+            ("1 * 2", Value::Integer(2.into())),
+            ("3 * 3 * 3", Value::Integer(27.into())),
+            ("4 * 5 - 3", Value::Integer(17.into())),
+            ("7 * 2", Value::Integer(14.into())),
+            ("10 / 2", Value::Integer(5.into())),
+            ("10 / 5", Value::Integer(2.into())),
+            ("10 * 10 / 5", Value::Integer(20.into())),
+            ("1 * 4 * 5 / 10 / 2 * 10", Value::Integer(10.into())),
+            ("3 ^ 0", Value::Integer(1.into())),
+            ("3 ^ 1", Value::Integer(3.into())),
+            ("3 ^ 2", Value::Integer(9.into())),
+            ("3 ^ 3", Value::Integer(27.into())),
+            ("-3 ^ 2", Value::Integer((-9).into())),
+            ("-3 ^ 3", Value::Integer((-27).into())),
+            ("(-3) ^ 2", Value::Integer(9.into())),
+            ("(-3) ^ 3", Value::Integer((-27).into())),
+            ("2 ^ 3 + 4", Value::Integer(12.into())),
+            ("3 ^ 2 + 4", Value::Integer(13.into())),
+            ("(-3) ^ 2 + 4", Value::Integer(13.into())),
+            ("2 ^ 3 * 4 + 4", Value::Integer(36.into())),
+            ("3 ^ 2 * 4 + 4", Value::Integer(40.into())),
+            ("(-3) ^ 2 * 4 + 4", Value::Integer(40.into())),
             ("true", Value::Bool(true)),
             ("false", Value::Bool(false)),
             ("true || false", Value::Bool(true)),
@@ -165,26 +252,37 @@ mod tests {
             ("9 <= 10", Value::Bool(true)),
             ("9 > 10", Value::Bool(false)),
             ("9 >= 10", Value::Bool(false)),
-            ("if true 1 else 2", Value::Integer(1)),
-            ("if !true 1 else 2", Value::Integer(2)),
-            ("{ let x = 1; x }", Value::Integer(1)),
-            ("{ let x = 1; let x = 2; x }", Value::Integer(2)),
-            ("{ let x = 1; { let x = 2 }; x }", Value::Integer(1)),
+            ("if true 1 else 2", Value::Integer(1.into())),
+            ("if !true 1 else 2", Value::Integer(2.into())),
+            ("if false 1", Value::Unit),
+            ("1 + if true 1 else 2", Value::Integer(2.into())),
+            ("(if false 1 else 2) * 3", Value::Integer(6.into())),
+            ("{ let x = { 1; 2 }; x }", Value::Integer(2.into())),
+            ("{ let x = 1 }", Value::Unit),
+            ("{ 1 } + { 2 }", Value::Integer(3.into())),
+            (
+                "[if true 1 else 2, { 3 }]",
+                Value::List(vec![Value::Integer(1.into()), Value::Integer(3.into())]),
+            ),
+            ("{ fn f() { let x = 1 }; f() }", Value::Unit),
+            ("{ let x = 1; x }", Value::Integer(1.into())),
+            ("{ let x = 1; let x = 2; x }", Value::Integer(2.into())),
+            ("{ let x = 1; { let x = 2 }; x }", Value::Integer(1.into())),
             ("[]", Value::List(vec![])),
-            ("[1]", Value::List(vec![Value::Integer(1)])),
-            ("[1,]", Value::List(vec![Value::Integer(1)])),
+            ("[1]", Value::List(vec![Value::Integer(1.into())])),
+            ("[1,]", Value::List(vec![Value::Integer(1.into())])),
             (
                 "[1,2]",
-                Value::List(vec![Value::Integer(1), Value::Integer(2)]),
+                Value::List(vec![Value::Integer(1.into()), Value::Integer(2.into())]),
             ),
             (
                 "[1,2,]",
-                Value::List(vec![Value::Integer(1), Value::Integer(2)]),
+                Value::List(vec![Value::Integer(1.into()), Value::Integer(2.into())]),
             ),
             (
                 "[0,false,!true,true&&false]",
                 Value::List(vec![
-                    Value::Integer(0),
+                    Value::Integer(0.into()),
                     Value::Bool(false),
                     Value::Bool(false),
                     Value::Bool(false),
@@ -197,6 +295,177 @@ mod tests {
             ("[1,2] != [1,2,3]", Value::Bool(true)),
             ("[1,2] != [2,1]", Value::Bool(true)),
             ("[1*1-1+1,(2)] == [1,2*1]", Value::Bool(true)),
+            ("{ fn foo(x) { x + 3 }; foo(1) }", Value::Integer(4.into())),
+            ("{ fn add(x, y) x + y; add(2, 3) * 2 }", Value::Integer(10.into())),
+            ("{ fn one() 1; -one() }", Value::Integer((-1).into())),
+            ("(fn(x) x * 2)(4)", Value::Integer(8.into())),
+            (
+                "{ let double = fn(x) x * 2; double(double(3)) }",
+                Value::Integer(12.into()),
+            ),
+            (
+                "{ let add = fn(x) fn(y) x + y; add(1)(2) }",
+                Value::Integer(3.into()),
+            ),
+            (
+                "{ let x = 10; let get = fn() x; { let x = 20; get() } }",
+                Value::Integer(10.into()),
+            ),
+            ("{ let x = 3; x = 4; x }", Value::Integer(4.into())),
+            ("{ let x = 3; { x = 4 }; x }", Value::Integer(4.into())),
+            ("{ let x = 3; { let x = 1; x = 4 }; x }", Value::Integer(3.into())),
+            ("{ let x = 1; x = x + 1; x = x * 3; x }", Value::Integer(6.into())),
+            ("{ let x = 1; x += 2; x }", Value::Integer(3.into())),
+            ("{ let x = 5; x -= 2 * 2; x }", Value::Integer(1.into())),
+            ("{ let x = 3; x *= 2 + 1; x }", Value::Integer(9.into())),
+            ("{ let x = 9; x /= 3; x }", Value::Integer(3.into())),
+            ("{ let x = 2; x ^= 3; x }", Value::Integer(8.into())),
+            ("{ let x = 1; x++ }", Value::Integer(1.into())),
+            ("{ let x = 1; ++x }", Value::Integer(2.into())),
+            ("{ let x = 1; x-- }", Value::Integer(1.into())),
+            ("{ let x = 1; --x }", Value::Integer(0.into())),
+            ("{ let x = 1; x++; x }", Value::Integer(2.into())),
+            ("{ let x = 1; -x++ }", Value::Integer((-1).into())),
+            ("{ let x = 1; -++x }", Value::Integer((-2).into())),
+            ("{ let x = 2; ++x ^ 2 }", Value::Integer(9.into())),
+            ("{ let x = 1; x++ + ++x }", Value::Integer(4.into())),
+            ("{ let x = 1; x++; --x; x += 2; x }", Value::Integer(3.into())),
+            ("5--3", Value::Integer(8.into())),
+            ("5++3", Value::Integer(8.into())),
+            ("--3", Value::Integer(3.into())),
+            ("(1)--1", Value::Integer(2.into())),
+            ("{ let x = 1; x-- - --x }", Value::Integer(2.into())),
+            ("{ let x = 5; x--3 }", Value::Integer(8.into())),
+            ("{ let x = 5; x++1 }", Value::Integer(6.into())),
+            (
+                "{ let x = 0; while x < 5 { x += 1 }; x }",
+                Value::Integer(5.into()),
+            ),
+            (
+                "{ let sum = 0; for i = 1; i <= 5; i += 1 { sum += i }; sum }",
+                Value::Integer(15.into()),
+            ),
+            (
+                "{ let x = 0; while true { x++; if x >= 3 break }; x }",
+                Value::Integer(3.into()),
+            ),
+            (
+                "{ let sum = 0; for i = 0; i < 10; i++ { if i > 4 continue; sum += i }; sum }",
+                Value::Integer(10.into()),
+            ),
+            (
+                "{ let n = 0; for i = 0; i < 3; i++ { for j = 0; j < 3; j++ { if j == 1 break; n++ } }; n }",
+                Value::Integer(3.into()),
+            ),
+            (
+                "{ let x = 0; while true { { { x++; if x == 2 { break } } } }; x }",
+                Value::Integer(2.into()),
+            ),
+            (
+                "{ let i = 10; for i = 0; i < 3; i++ { i }; i }",
+                Value::Integer(10.into()),
+            ),
+            (
+                "for x in [0, 1, 2, 3] { x * 2 }",
+                Value::List(vec![
+                    Value::Integer(0.into()),
+                    Value::Integer(2.into()),
+                    Value::Integer(4.into()),
+                    Value::Integer(6.into()),
+                ]),
+            ),
+            ("for x in [] x", Value::List(vec![])),
+            (
+                "for x in [1, 2, 3, 4] { if x == 2 continue; if x == 4 break; x }",
+                Value::List(vec![Value::Integer(1.into()), Value::Integer(3.into())]),
+            ),
+            (
+                "{ let x = 10; for x in [1] { x = 5 }; x }",
+                Value::Integer(10.into()),
+            ),
+            (
+                "{ let sum = 0; for x in [1, 2, 3] { sum += x }; sum }",
+                Value::Integer(6.into()),
+            ),
+            (
+                "{ let n = 0; fn inc() { n = n + 1; n }; inc(); inc() }",
+                Value::Integer(2.into()),
+            ),
+            (
+                "{ fn fact(n) if n <= 1 1 else n * fact(n - 1); fact(5) }",
+                Value::Integer(120.into()),
+            ),
+            ("2.75", Value::Float(2.75)),
+            ("1e-9", Value::Float(1e-9)),
+            ("-2.5", Value::Float(-2.5)),
+            ("1 + 0.5", Value::Float(1.5)),
+            ("0.5 * 4", Value::Float(2.0)),
+            ("7 / 2", Value::Integer(3.into())),
+            ("7 /. 2", Value::Float(3.5)),
+            ("7.0 / 2", Value::Float(3.5)),
+            ("2 ^ 0.5 == 2.0 ^ 0.5", Value::Bool(true)),
+            ("2.0 ^ -1", Value::Float(0.5)),
+            ("1 == 1.0", Value::Bool(true)),
+            ("1 < 1.5", Value::Bool(true)),
+            ("2.5 >= 3", Value::Bool(false)),
+            ("{ let x = 1.5; x++; x }", Value::Float(2.5)),
+            ("{ let x = 3; x *= 1.5; x }", Value::Float(4.5)),
+            ("\"{0.1 + 0.2}\"", Value::Str("0.30000000000000004".into())),
+            ("-7 / 2", Value::Integer((-3).into())),
+            ("-7 // 2", Value::Integer((-4).into())),
+            ("7 // -2", Value::Integer((-4).into())),
+            ("-7 % 2", Value::Integer((-1).into())),
+            ("7 % -2", Value::Integer(1.into())),
+            ("-7 mod 2", Value::Integer(1.into())),
+            ("-7 mod -2", Value::Integer(1.into())),
+            ("7 mod 3 * 2", Value::Integer(2.into())),
+            ("1 + 7 % 4", Value::Integer(4.into())),
+            ("2 ^ 65 // 3 == (2 ^ 65 - 2) / 3", Value::Bool(true)),
+            ("-7.5 // 2", Value::Float(-4.0)),
+            ("-7.5 % 2", Value::Float(-1.5)),
+            ("-7.5 mod 2", Value::Float(0.5)),
+            ("12 & 10", Value::Integer(8.into())),
+            ("12 | 10", Value::Integer(14.into())),
+            ("12 ^^ 10", Value::Integer(6.into())),
+            ("~5", Value::Integer((-6).into())),
+            ("~-1", Value::Integer(0.into())),
+            ("1 << 4", Value::Integer(16.into())),
+            ("-16 >> 2", Value::Integer((-4).into())),
+            ("1 << 2 + 1", Value::Integer(8.into())),
+            ("6 & 3 == 2", Value::Bool(true)),
+            ("1 | 2 ^^ 3 & 5", Value::Integer(3.into())),
+            ("{ let flags = 0; flags = flags | 1 << 3; flags & 8 != 0 }", Value::Bool(true)),
+            ("(1 << 70) >> 68", Value::Integer(4.into())),
+            ("\"hello\"", Value::Str("hello".into())),
+            ("\"foo\" + \"bar\"", Value::Str("foobar".into())),
+            ("\"total: \" + \"{2 * 3}\"", Value::Str("total: 6".into())),
+            ("{ let s = \"a\"; s += \"b\"; s }", Value::Str("ab".into())),
+            ("\"abc\" == \"abc\"", Value::Bool(true)),
+            ("\"abc\" != \"abd\"", Value::Bool(true)),
+            ("\"abc\" < \"abd\"", Value::Bool(true)),
+            ("\"b\" > \"abc\"", Value::Bool(true)),
+            ("\"\" <= \"a\"", Value::Bool(true)),
+            ("\"{1 + 2}\"", Value::Str("3".into())),
+            (
+                "{ let x = 41; \"total: {x + 1}!\" }",
+                Value::Str("total: 42!".into()),
+            ),
+            (
+                "{ let xs = [\"a\", 1]; \"{xs} {xs == xs} {\"s{1}\"}\" }",
+                Value::Str("[\"a\", 1] true s1".into()),
+            ),
+            (
+                "\"{ { let y = 2; y * 3 } } \\{}\"",
+                Value::Str("6 {}".into()),
+            ),
+            (
+                "{ let x = 1; \"\\{x\\} \\{x} {{x}}\" }",
+                Value::Str("{x} {x} 1".into()),
+            ),
+            (
+                "for c in [\"a\", \"b\"] c + \"!\"",
+                Value::List(vec![Value::Str("a!".into()), Value::Str("b!".into())]),
+            ),
         ]
         .into_iter()
         .for_each(|(string, val)| {
@@ -211,6 +480,1149 @@ mod tests {
     fn let_statement() {
         let mut eval = Evaluator::new();
         let values = process_string(&mut eval, "let x = 42; x".into(), false).unwrap();
-        assert_eq!(vec![None, Some(Value::Integer(42))], values);
+        assert_eq!(vec![None, Some(Value::Integer(42.into()))], values);
+    }
+
+    #[test]
+    fn assignment_to_unknown_identifier() {
+        let mut eval = Evaluator::new();
+        let result = process_string(&mut eval, "{ let y = 1; x = 4 }".into(), false);
+
+        assert!(matches!(
+            result,
+            Err(IntyError::UnknownIdentifier { ident, .. }) if ident == "x"
+        ));
+    }
+
+    #[test]
+    fn loop_signal_errors() {
+        [
+            "break",
+            "continue",
+            "{ 1; break; 2 }",
+            "fn stop() break; while true { stop() }",
+        ]
+        .into_iter()
+        .for_each(|string| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert!(
+                matches!(result, Err(IntyError::LogicError { .. })),
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn iterating_non_iterable() {
+        let mut eval = Evaluator::new();
+        let result = process_string(&mut eval, "for x in 42 { x }".into(), false);
+
+        assert!(matches!(
+            result,
+            Err(IntyError::TypeError { message, .. }) if message == "42 is not iterable"
+        ));
+    }
+
+    #[test]
+    fn error_spans() {
+        [
+            ("1 +\n  x", Span::new(6, 7, 2, 3)),
+            ("let y = 2;\ny / 0", Span::new(11, 16, 2, 1)),
+            ("[1, 2] + 3", Span::new(0, 6, 1, 1)),
+            ("if 1 {\n  true + 1\n}", Span::new(9, 13, 2, 3)),
+            (
+                "while true {\n  1;\n  break\n}; continue",
+                Span::new(29, 37, 4, 4),
+            ),
+            ("fn f(x) x; f(1,\n 2)", Span::new(11, 19, 1, 12)),
+            ("{ 1 + }", Span::new(6, 7, 1, 7)),
+            ("(1 + 2", Span::new(6, 6, 1, 7)),
+            ("1 $ 2", Span::new(2, 3, 1, 3)),
+        ]
+        .into_iter()
+        .for_each(|(string, span)| {
+            let mut eval = Evaluator::new();
+            let err = process_string(&mut eval, string.to_string(), false).unwrap_err();
+
+            assert_eq!(err.span(), Some(span), "expression = \"{}\"", string);
+        })
+    }
+
+    #[test]
+    fn repl_error_in_earlier_line() {
+        let mut eval = Evaluator::new();
+        let mut session = Session::default();
+
+        let start = session.push_line("fn f(x) { 1; 2; 3; 4; 5; x / 0 }");
+        let stmts = parse_string(&session.source, start, false).unwrap();
+        eval.eval(stmts).unwrap();
+
+        // The error comes from the first line, which has to be rendered instead of the second
+        let start = session.push_line("\"éééééééééééééééé\" + f(1)");
+        assert_eq!(start, (33, 2));
+
+        let stmts = parse_string(&session.source, start, false).unwrap();
+        let err = eval.eval(stmts).unwrap_err();
+
+        assert_eq!(
+            Diagnostic::from(&err).render("<repl>", &session.source, false),
+            [
+                "error: cannot divide by zero",
+                " --> <repl>:1:26",
+                "  |",
+                "1 | fn f(x) { 1; 2; 3; 4; 5; x / 0 }",
+                "  |                          ^^^^^ division by zero",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn syntax_error_recovery() {
+        [
+            ("1 + 2", vec![]),
+            (
+                "let = 1; let y = 2; y +",
+                vec![Span::new(4, 5, 1, 5), Span::new(23, 23, 1, 24)],
+            ),
+            (
+                "let x = 1 +\nlet y = 2\nlet z = *",
+                vec![
+                    Span::new(12, 15, 2, 1),
+                    Span::new(22, 25, 3, 1),
+                    Span::new(30, 31, 3, 9),
+                ],
+            ),
+            (
+                "{ 1 + ; 2 }; 3 4; 5",
+                vec![Span::new(6, 7, 1, 7), Span::new(15, 16, 1, 16)],
+            ),
+            (
+                "{ let }; { (1 }; }; 2",
+                vec![
+                    Span::new(6, 7, 1, 7),
+                    Span::new(14, 15, 1, 15),
+                    Span::new(17, 18, 1, 18),
+                ],
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, spans)| {
+            let tokens = Lexer::tokenize(string.to_string()).unwrap();
+            let (_, errors) = Parser::new(&tokens).parse_with_recovery();
+
+            assert_eq!(
+                errors.iter().map(|err| err.span()).collect::<Vec<_>>(),
+                spans.into_iter().map(Some).collect::<Vec<_>>(),
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn value_display() {
+        [
+            ("\"hi\"", "hi"),
+            ("\"a\\tb\"", "a\tb"),
+            ("1.0", "1.0"),
+            ("6 /. 3", "2.0"),
+            ("1e-9", "1e-9"),
+            ("1e21", "1e21"),
+            ("[0.1, 2]", "[0.1, 2]"),
+            ("[\"hi\", 1, [\"a\\\"b\"]]", "[\"hi\", 1, [\"a\\\"b\"]]"),
+            ("\"list: {[\"x\"]}\"", "list: [\"x\"]"),
+            ("0..10", "0..10"),
+            ("[10..=0 step -2]", "[10..=0 step -2]"),
+        ]
+        .into_iter()
+        .for_each(|(string, display)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(
+                results[0].as_ref().unwrap().to_string(),
+                display,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn interpolation_errors() {
+        [
+            ("\"a {} b\"", Span::new(0, 8, 1, 1)),
+            ("\"a{\"", Span::new(2, 4, 1, 3)),
+            ("\"{1 2}\"", Span::new(4, 5, 1, 5)),
+            ("\"{1 +}\"", Span::new(5, 5, 1, 6)),
+            ("\"{y}\"", Span::new(2, 3, 1, 3)),
+        ]
+        .into_iter()
+        .for_each(|(string, span)| {
+            let mut eval = Evaluator::new();
+            let err = process_string(&mut eval, string.to_string(), false).unwrap_err();
+
+            assert_eq!(err.span(), Some(span), "expression = \"{}\"", string);
+        })
+    }
+
+    #[test]
+    fn string_errors() {
+        [
+            (
+                "\"total: \" + 42",
+                "type error: cannot add integer to string",
+            ),
+            ("[1] + \" items\"", "type error: cannot add string to list"),
+            ("\"a{\"", "syntax error: unterminated string interpolation"),
+            ("\"a{1 +", "syntax error: unterminated string interpolation"),
+            (
+                "{ let s = \"a\"; s += true }",
+                "type error: cannot add boolean to string",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                message,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn big_integers() {
+        [
+            (
+                "2 ^ 200",
+                "1606938044258990275541962092341162602522202993782792835301376",
+            ),
+            (
+                "{ fn fact(n) if n <= 1 1 else n * fact(n - 1); fact(30) }",
+                "265252859812191058636308480000000",
+            ),
+            ("9223372036854775807 + 1", "9223372036854775808"),
+            ("-9223372036854775807 - 2", "-9223372036854775809"),
+            ("(2 ^ 100 + 1) - 2 ^ 100", "1"),
+            ("2 ^ 100 / 2 ^ 98", "4"),
+            ("99999999999999999999999 * 0", "0"),
+            ("2 ^ 64 > 2 ^ 63", "true"),
+            ("2 ^ 64 == 18446744073709551616", "true"),
+            ("2 ^ 64 /. 2", "9.223372036854776e18"),
+            ("\"{-(2 ^ 70)}\"", "-1180591620717411303424"),
+        ]
+        .into_iter()
+        .for_each(|(string, display)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(
+                results[0].as_ref().unwrap().to_string(),
+                display,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn overflow_errors() {
+        [
+            (
+                "2 ^ 99999999999",
+                "overflow: `2 ^ 99999999999` is too large",
+            ),
+            (
+                "{ let x = 10; x ^= 9999999 }",
+                "overflow: `10 ^ 9999999` is too large",
+            ),
+            ("1e308 * 10", "overflow: `1e308 * 10.0` is too large"),
+            ("2.0 ^ 2000", "overflow: `2.0 ^ 2000.0` is too large"),
+            (
+                "1e999",
+                "could not parse token 1e999: number is too large to be a float",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let mut eval = Evaluator::new();
+            let err = process_string(&mut eval, string.to_string(), false).unwrap_err();
+
+            assert_eq!(err.to_string(), message, "expression = \"{}\"", string);
+        });
+
+        // Operations that overflow fixed size integers are promoted instead
+        [
+            ("-(-9223372036854775808)", "9223372036854775808"),
+            ("-9223372036854775808 / -1", "9223372036854775808"),
+            ("1 ^ 99999999999", "1"),
+            ("(-1) ^ 99999999999", "-1"),
+        ]
+        .into_iter()
+        .for_each(|(string, display)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(
+                results[0].as_ref().unwrap().to_string(),
+                display,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn logical_short_circuit() {
+        [
+            ("false && (1 / 0 == 0)", Value::Bool(false)),
+            ("true || undefined", Value::Bool(true)),
+            ("false && [1] + 1", Value::Bool(false)),
+            (
+                "{ let xs = []; xs == [1] && 1 / 0 == 0 }",
+                Value::Bool(false),
+            ),
+            (
+                "{ let n = 0; fn bump() { n++; true }; false && bump(); true || bump(); n }",
+                Value::Integer(0.into()),
+            ),
+            (
+                "{ let n = 0; fn bump() { n++; true }; true && bump(); false || bump(); n }",
+                Value::Integer(2.into()),
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, val)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(results[0], Some(val), "expression = \"{}\"", string);
+        });
+
+        // The right side is still evaluated when it decides the result
+        let mut eval = Evaluator::new();
+        assert!(matches!(
+            process_string(&mut eval, "true && (1 / 0 == 0)".into(), false),
+            Err(IntyError::DivideByZeroError { .. })
+        ));
+    }
+
+    #[test]
+    fn bitwise_errors() {
+        let mut eval = Evaluator::new();
+
+        assert!(matches!(
+            process_string(&mut eval, "1 << -1".into(), false),
+            Err(IntyError::LogicError { .. })
+        ));
+        assert!(matches!(
+            process_string(&mut eval, "1.5 & 1".into(), false),
+            Err(IntyError::TypeError { .. })
+        ));
+        assert!(matches!(
+            process_string(&mut eval, "~true".into(), false),
+            Err(IntyError::TypeError { .. })
+        ));
+        assert!(matches!(
+            process_string(&mut eval, "1 << 99999999999".into(), false),
+            Err(IntyError::OverflowError { .. })
+        ));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        [
+            "1 / 0",
+            "1 /. 0",
+            "1 // 0",
+            "1 % 0",
+            "1 mod 0",
+            "1.5 % 0",
+            "2 ^ 70 mod 0.0",
+        ]
+        .into_iter()
+        .for_each(|string| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert!(
+                matches!(result, Err(IntyError::DivideByZeroError { .. })),
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn function_call_errors() {
+        [
+            "fn foo(x) x; foo()",
+            "fn foo(x) x; foo(1, 2)",
+            "let x = 1; x(1)",
+        ]
+        .into_iter()
+        .for_each(|string| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert!(result.is_err(), "expression = \"{}\"", string);
+        })
+    }
+
+    #[test]
+    fn truthiness_modes() {
+        [
+            ("true && -1", Truthiness::Legacy, Value::Bool(false)),
+            ("true && -1", Truthiness::Conventional, Value::Bool(true)),
+            ("!0", Truthiness::Conventional, Value::Bool(true)),
+            ("!0.5", Truthiness::Conventional, Value::Bool(false)),
+            (
+                "if -3 1 else 2",
+                Truthiness::Legacy,
+                Value::Integer(2.into()),
+            ),
+            (
+                "if -3 1 else 2",
+                Truthiness::Conventional,
+                Value::Integer(1.into()),
+            ),
+            ("!(1 < 2)", Truthiness::Strict, Value::Bool(false)),
+            (
+                "{ let n = 3; while n n--; n }",
+                Truthiness::Conventional,
+                Value::Integer(0.into()),
+            ),
+            // Options carry over into function bodies and nested scopes
+            (
+                "{ fn f(x) { x || false }; f(-2) }",
+                Truthiness::Conventional,
+                Value::Bool(true),
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, truthiness, val)| {
+            let mut eval = Evaluator::new().with_truthiness(truthiness);
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(results[0], Some(val), "expression = \"{}\"", string);
+        })
+    }
+
+    #[test]
+    fn strict_truthiness_errors() {
+        [
+            "true && 1",
+            "0 || true",
+            "!1",
+            "if 1 2",
+            "while 1 break",
+            "for x in [1] { if x 2 }",
+            "{ fn f() !1; f() }",
+        ]
+        .into_iter()
+        .for_each(|string| {
+            let mut eval = Evaluator::new().with_truthiness(Truthiness::Strict);
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert!(
+                matches!(result, Err(IntyError::TypeError { .. })),
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn indexing_and_slicing() {
+        let list =
+            |vals: &[i64]| Value::List(vals.iter().map(|&v| Value::Integer(v.into())).collect());
+
+        [
+            ("[1, 2, 3][0]", Value::Integer(1.into())),
+            ("{ let xs = [1, 2, 3]; xs[-1] }", Value::Integer(3.into())),
+            (
+                "{ let xs = [1, 2, 3]; xs[1 + 1] }",
+                Value::Integer(3.into()),
+            ),
+            ("[[1, 2], [3, 4]][1][0]", Value::Integer(3.into())),
+            ("[1, 2, 3, 4][1:3]", list(&[2, 3])),
+            ("[1, 2, 3, 4][:-1]", list(&[1, 2, 3])),
+            ("[1, 2, 3, 4][2:]", list(&[3, 4])),
+            ("[1, 2, 3, 4][:]", list(&[1, 2, 3, 4])),
+            ("[1, 2, 3, 4][3:1]", list(&[])),
+            ("[1, 2, 3][3:]", list(&[])),
+            ("\"héllo\"[1]", Value::Str("é".into())),
+            ("\"hello\"[-3:]", Value::Str("llo".into())),
+            ("{ fn f() [1, 2]; f()[1] }", Value::Integer(2.into())),
+        ]
+        .into_iter()
+        .for_each(|(string, val)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(results[0], Some(val), "expression = \"{}\"", string);
+        })
+    }
+
+    #[test]
+    fn index_errors() {
+        [
+            (
+                "[1, 2, 3][3]",
+                "index out of bounds: the length is 3 but the index is 3",
+            ),
+            (
+                "[1, 2, 3][-4]",
+                "index out of bounds: the length is 3 but the index is -4",
+            ),
+            (
+                "[][0]",
+                "index out of bounds: the length is 0 but the index is 0",
+            ),
+            (
+                "[1][0:2]",
+                "index out of bounds: the length is 1 but the index is 2",
+            ),
+            (
+                "[1][2 ^ 80]",
+                "index out of bounds: the length is 1 but the index is 1208925819614629174706176",
+            ),
+            ("[1][true]", "type error: true is not an integer"),
+            ("5[0]", "type error: 5 is not indexable"),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                message,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn list_operators() {
+        let list =
+            |vals: &[i64]| Value::List(vals.iter().map(|&v| Value::Integer(v.into())).collect());
+
+        [
+            ("[1] + [2, 3]", list(&[1, 2, 3])),
+            ("[] + []", list(&[])),
+            ("[0] * 3", list(&[0, 0, 0])),
+            ("2 * [1, 2]", list(&[1, 2, 1, 2])),
+            ("[1, 2] * 0", list(&[])),
+            ("2 in [1, 2, 3]", Value::Bool(true)),
+            ("4 in [1, 2, 3]", Value::Bool(false)),
+            ("2.0 in [1, 2]", Value::Bool(true)),
+            ("\"b\" in [1, \"b\"]", Value::Bool(true)),
+            ("[1] in [[1], [2]]", Value::Bool(true)),
+            ("\"ell\" in \"hello\"", Value::Bool(true)),
+            ("[1, 2] < [1, 3]", Value::Bool(true)),
+            ("[1, 2] < [1, 2, 0]", Value::Bool(true)),
+            ("[2] > [1, 9]", Value::Bool(true)),
+            ("[1, 2] >= [1, 2]", Value::Bool(true)),
+            ("[[1, 2]] < [[1, 3]]", Value::Bool(true)),
+            ("[1, \"a\"] == [1, 2]", Value::Bool(false)),
+            ("[1] != [true]", Value::Bool(true)),
+        ]
+        .into_iter()
+        .for_each(|(string, val)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(results[0], Some(val), "expression = \"{}\"", string);
+        })
+    }
+
+    #[test]
+    fn comparison_errors() {
+        [
+            (
+                "1 == \"1\"",
+                "type error: cannot compare integer with string",
+            ),
+            ("[1] < 2", "type error: cannot compare list with integer"),
+            (
+                "true < false",
+                "type error: cannot use `<` on boolean values",
+            ),
+            (
+                "[1] < [\"a\"]",
+                "type error: cannot compare integer with string",
+            ),
+            (
+                "1 in 2",
+                "type error: cannot check if integer is in integer",
+            ),
+            (
+                "1 in \"1\"",
+                "type error: cannot check if integer is in string",
+            ),
+            (
+                "[0] * -1",
+                "logic error: repeat count must be a non-negative integer",
+            ),
+            (
+                "[0] * 2 ^ 64",
+                "overflow: `[0] * 18446744073709551616` is too large",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                message,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn ranges() {
+        let list =
+            |vals: &[i64]| Value::List(vals.iter().map(|&v| Value::Integer(v.into())).collect());
+
+        [
+            ("for i in 0..4 i", list(&[0, 1, 2, 3])),
+            ("for i in 0..=4 i", list(&[0, 1, 2, 3, 4])),
+            ("for i in 0..10 step 3 i", list(&[0, 3, 6, 9])),
+            ("for i in 10..=0 step -5 i", list(&[10, 5, 0])),
+            ("for i in 3..0 i", list(&[])),
+            ("{ let n = 2; for i in 0..n + 1 i * i }", list(&[0, 1, 4])),
+            ("(0..10)[3]", Value::Integer(3.into())),
+            ("(0..10 step 2)[-1]", Value::Integer(8.into())),
+            ("(0..2 ^ 100)[-1] == 2 ^ 100 - 1", Value::Bool(true)),
+            ("(0..10)[2:5] == 2..5", Value::Bool(true)),
+            ("for i in (0..10 step 3)[1:] i", list(&[3, 6, 9])),
+            ("0..3 == 0..=2", Value::Bool(true)),
+            ("0..3 == [0, 1, 2]", Value::Bool(true)),
+            ("[0, 1] < 0..3", Value::Bool(true)),
+            ("0..3 < 0..3 step 2", Value::Bool(true)),
+            ("5..0 == []", Value::Bool(true)),
+            ("4 in 0..10 step 2", Value::Bool(true)),
+            ("5 in 0..10 step 2", Value::Bool(false)),
+            ("10 in 0..10", Value::Bool(false)),
+            ("10 in 0..=10", Value::Bool(true)),
+            ("1.0 in 0..3", Value::Bool(true)),
+            ("1.5 in 0..3", Value::Bool(false)),
+            ("3.0 in 0..3", Value::Bool(false)),
+            ("2.0 in 0..=4 step 2", Value::Bool(true)),
+            ("(0..3) + [3]", list(&[0, 1, 2, 3])),
+            ("[3] + (0..3)", list(&[3, 0, 1, 2])),
+            ("[...0..3]", list(&[0, 1, 2])),
+            ("[-1, ...0..2, ...[5, 6], 7]", list(&[-1, 0, 1, 5, 6, 7])),
+            ("[...(0..2) + [2]]", list(&[0, 1, 2])),
+            ("(0..2) * 2", list(&[0, 1, 0, 1])),
+            (
+                "0..=10 step 2",
+                Value::Range(Range::new(0.into(), 10.into(), 2.into(), true).unwrap()),
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, val)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(results[0], Some(val), "expression = \"{}\"", string);
+        })
+    }
+
+    #[test]
+    fn range_errors() {
+        [
+            ("0..10 step 0", "logic error: range step must not be zero"),
+            ("0..1.5", "type error: 1.5 is not an integer"),
+            ("[...5]", "type error: 5 is not a list"),
+            ("...(0..3)", "syntax error: unexpected token ..."),
+            (
+                "(0..3)[3]",
+                "index out of bounds: the length is 3 but the index is 3",
+            ),
+            (
+                "(0..2 ^ 80) + []",
+                "overflow: `0..1208925819614629174706176` is too large",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                message,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn maps() {
+        [
+            ("{ \"a\": 1, 2: true }[\"a\"]", "1"),
+            ("{ \"a\": 1, 2: true }[2]", "true"),
+            ("{ \"a\": 1, \"a\": 2 }", "{\"a\": 2}"),
+            ("{:}", "{:}"),
+            ("{1: 1} == {1: 1.0}", "true"),
+            ("[{1: [1]}] == [{1: [1.0]}]", "true"),
+            ("{1: 1} == {1: \"1\"}", "false"),
+            ("{1: 0} == {1.0: 0}", "true"),
+            ("{1: 2}[1.0]", "2"),
+            ("let m = {1: 2}; m[1.0] = 3; m", "{1: 3}"),
+            ("{1: 2, 1.0: 3}", "{1: 3}"),
+            ("1.0 in {1: 2}", "true"),
+            ("{1: 1, 2: 2} != {2: 2.0, 1: 1.0}", "false"),
+            ("{ let k = \"x\"; { k: k + \"!\" } }", "{\"x\": \"x!\"}"),
+            (
+                "let m = {:}; m[\"b\"] = 1; m[\"a\"] = 2; m[\"b\"] = 3; m",
+                "{\"b\": 3, \"a\": 2}",
+            ),
+            (
+                "let m = { \"a\": 1, \"b\": 2 }; delete m[\"a\"]; m",
+                "{\"b\": 2}",
+            ),
+            (
+                "let m = { \"a\": [1, { \"b\": 2 }] }; m[\"a\"][1][\"c\"] = 3; m",
+                "{\"a\": [1, {\"b\": 2, \"c\": 3}]}",
+            ),
+            ("let xs = [1, 2, 3]; xs[-1] = 4; delete xs[0]; xs", "[2, 4]"),
+            ("for k in { \"x\": 1, \"y\": 2 } k", "[\"x\", \"y\"]"),
+            ("{ \"a\": 1, \"b\": 2 } == { \"b\": 2, \"a\": 1 }", "true"),
+            ("{ \"a\": 1 } != { \"a\": 2 }", "true"),
+            ("{ \"a\": 1 } == { \"a\": 1, \"b\": 2 }", "false"),
+            ("\"a\" in { \"a\": 1 }", "true"),
+            ("1 in { \"a\": 1 }", "false"),
+            ("if true {} else 1", "()"),
+        ]
+        .into_iter()
+        .for_each(|(string, display)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(
+                results.last().unwrap().as_ref().unwrap().to_string(),
+                display,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn map_errors() {
+        [
+            ("{ \"a\": 1 }[\"b\"]", "key not found: \"b\""),
+            ("let m = {:}; delete m[1]", "key not found: 1"),
+            ("let m = {:}; m[\"a\"][\"b\"] = 1", "key not found: \"a\""),
+            (
+                "let s = \"abc\"; s[0] = \"x\"",
+                "type error: abc cannot be changed by index",
+            ),
+            ("m[0] = 1", "unknown identifier: m"),
+            (
+                "{ \"a\": 1 } < { \"a\": 2 }",
+                "type error: cannot use `<` on map values",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                message,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn tuples_and_destructuring() {
+        [
+            ("(1, true)", "(1, true)"),
+            ("(\"a\",)", "(\"a\",)"),
+            ("(1 + 2)", "3"),
+            ("(1, (2, 3))[1][0]", "2"),
+            ("(1, 2) == (1, 2)", "true"),
+            ("(1, 2) < (1, 3)", "true"),
+            ("let (a, b) = (1, 2); a + b", "3"),
+            ("let (a, b) = (1, 2); let (a, b) = (b, a); [a, b]", "[2, 1]"),
+            ("let (a, (b, _)) = (1, (2, 3)); [a, b]", "[1, 2]"),
+            (
+                "let [head, ...rest] = [1, 2, 3]; [head, rest]",
+                "[1, [2, 3]]",
+            ),
+            ("let [a, ...rest] = [1]; rest", "[]"),
+            ("let [_, b, ...] = [1, 2, 3]; b", "2"),
+            ("let [(k, v)] = [(\"a\", 1)]; \"{k}{v}\"", "a1"),
+            ("let (x) = 5; x", "5"),
+        ]
+        .into_iter()
+        .for_each(|(string, display)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(
+                results.last().unwrap().as_ref().unwrap().to_string(),
+                display,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn pattern_mismatch_errors() {
+        [
+            ("let (a, b) = (1, 2, 3)", "expected a tuple of 2 elements, found (1, 2, 3)"),
+            ("let (a, b) = [1, 2]", "expected a tuple of 2 elements, found [1, 2]"),
+            ("let [a] = [1, 2]", "expected a list of 1 element, found [1, 2]"),
+            ("let [a, b, ...rest] = [1]", "expected a list of at least 2 elements, found [1]"),
+            ("let (a, [b]) = (1, \"x\")", "expected a list of 1 element, found \"x\""),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert!(
+                matches!(&result, Err(IntyError::PatternMismatch { message: m, .. }) if m == message),
+                "expression = \"{}\", result = {:?}",
+                string,
+                result
+            );
+        });
+
+        // Nothing is bound when only part of a pattern matches
+        let mut eval = Evaluator::new();
+        let _ = process_string(&mut eval, "let (a, [b]) = (1, 2)".into(), false);
+        assert!(process_string(&mut eval, "a".into(), false).is_err());
+    }
+
+    #[test]
+    fn structs() {
+        [
+            (
+                "struct Point { x, y }; Point { x: 1, y: 2 }",
+                "Point { x: 1, y: 2 }",
+            ),
+            ("struct Point { x, y }; Point { y: 2, x: 1 }.x", "1"),
+            (
+                "struct Point { x, y }; let p = Point { x: 1, y: 2 }; p.x + p.y",
+                "3",
+            ),
+            (
+                "struct Point { x, y }; let p = Point { x: 1, y: 2 }; Point { y: 5, ..p }",
+                "Point { x: 1, y: 5 }",
+            ),
+            (
+                "struct Point { x, y }; let x = 3; let y = 4; Point { x, y }",
+                "Point { x: 3, y: 4 }",
+            ),
+            (
+                "struct Point { x, y }; Point { x: 1, y: 2 } == Point { y: 2, x: 1 }",
+                "true",
+            ),
+            (
+                "struct Point { x, y }; Point { x: 1, y: 2 } != Point { x: 1, y: 3 }",
+                "true",
+            ),
+            (
+                "struct Line { a, b }; struct P { v }; Line { a: P { v: 1 }, b: P { v: \"s\" } }",
+                "Line { a: P { v: 1 }, b: P { v: \"s\" } }",
+            ),
+            ("struct Point { x, y }; Point", "struct Point { x, y }"),
+            ("struct Empty {}; Empty {}", "Empty {}"),
+            (
+                "struct Point { x, y }; fn origin() Point { x: 0, y: 0 }; origin().y",
+                "0",
+            ),
+            ("struct P { x }; let p = P; if p == P { 1 } else { 2 }", "1"),
+            (
+                "struct P { x }; let p = P { x: 1 }; if p == (P { x: 1 }) { 1 } else { 2 }",
+                "1",
+            ),
+            (
+                "struct P { x }; let p = P; let n = 0; while p == P && n < 3 { n += 1 }; n",
+                "3",
+            ),
+            (
+                "struct P { x }; let p = P; let n = 0; for i = 0; i < 3; p = P { i += 1; n += 1 }; n",
+                "3",
+            ),
+            (
+                "struct P { x }; if { P { x: 1 } }.x == 1 { [P { x: 2 }][0].x } else { 0 }",
+                "2",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, display)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(
+                results.last().unwrap().as_ref().unwrap().to_string(),
+                display,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn struct_errors() {
+        [
+            (
+                "struct Point { x, y }; Point { x: 1 }",
+                "type error: missing `y` in Point",
+            ),
+            (
+                "struct Point { x, y }; Point { x: 1, y: 2, z: 3 }",
+                "unknown field `z` on Point",
+            ),
+            (
+                "struct Point { x, y }; Point { x: 1, y: 2 }.z",
+                "unknown field `z` on Point",
+            ),
+            (
+                "struct A { x }; struct B { x }; B { ..A { x: 1 } }",
+                "type error: A { x: 1 } is not a B record",
+            ),
+            ("let p = 1; p.x", "type error: 1 is not a record"),
+            (
+                "fn Point() 1; Point { x: 1 }",
+                "type error: fn Point() is not a struct",
+            ),
+            ("Point { x: 1 }", "unknown identifier: Point"),
+            (
+                "struct point { x }",
+                "syntax error: expected type name starting with an uppercase letter",
+            ),
+            (
+                "struct P { x, x }",
+                "syntax error: field `x` is declared more than once",
+            ),
+            (
+                "struct P { x }; for x in P { x }",
+                "type error: struct P { x } is not iterable",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.to_string(), false);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                message,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn enums_and_match() {
+        let shape = "enum Shape { Circle(r), Rect(w, h), Empty }; ";
+        [
+            ("Circle(2)", "Circle(2)"),
+            ("Rect(1, \"a\")", "Rect(1, \"a\")"),
+            ("Empty", "Empty"),
+            ("Shape", "enum Shape { Circle(r), Rect(w, h), Empty }"),
+            ("Circle", "fn Circle(r)"),
+            ("Circle(1) == Circle(1)", "true"),
+            ("Circle(1) == Rect(1, 1)", "false"),
+            ("match Circle(2) { Circle(r) => r * r, Rect(w, h) => w * h, Empty => 0 }", "4"),
+            ("match Rect(2, 3) { Circle(r) => r * r, Rect(w, h) => w * h, Empty => 0 }", "6"),
+            ("match Empty { Circle(_) => 1, _ => 0 }", "0"),
+            (
+                "match Rect(2, 2) { Rect(w, h) if w == h => \"square\", Rect(w, h) => w * h, s => s }",
+                "square",
+            ),
+            (
+                "match Rect(2, 3) { Rect(w, h) if w == h => \"square\", s => s }",
+                "Rect(2, 3)",
+            ),
+            ("match Circle((1, 2)) { Circle((a, b)) => a + b, _ => 0 }", "3"),
+            ("let s = Empty; if s == Empty { 1 } else { 2 }", "1"),
+            (
+                "let s = Circle(1); let n = 0; while s != Empty { n += 1; if n == 2 { s = Empty } }; n",
+                "2",
+            ),
+            (
+                "let s = Circle(1); let n = 0; for i = 0; i < 3; s = Empty { i += 1; n += 1 }; s",
+                "Empty",
+            ),
+            (
+                "let s = Empty; if s == Empty { match s { Empty => 1, _ => 2 } } else { 0 }",
+                "1",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, display)| {
+            let string = format!("{}{}", shape, string);
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.clone(), false).unwrap();
+
+            assert_eq!(
+                results.last().unwrap().as_ref().unwrap().to_string(),
+                display,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn match_patterns() {
+        [
+            ("match 2 { 1 => \"one\", 2 => \"two\", _ => \"many\" }", "two"),
+            ("match -1.5 { -1.5 => true, _ => false }", "true"),
+            ("match 2.0 { 2 => \"equal\", _ => \"not equal\" }", "equal"),
+            ("match \"hi\" { \"bye\" => 1, \"hi\" => 2 }", "2"),
+            ("match true { false => 0, true => 1 }", "1"),
+            ("match 5 { x if x > 3 => x * 2, x => x }", "10"),
+            ("match 2 { x if x > 3 => x * 2, x => x }", "2"),
+            ("match (1, \"a\") { (1, \"b\") => 1, (1, s) => s }", "a"),
+            ("match [1, 2, 3] { [] => 0, [x] => x, [_, ...rest] => rest }", "[2, 3]"),
+            ("match [] { [] => \"empty\", _ => \"items\" }", "empty"),
+            ("let x = 3; match x { 3 => { let y = 2; y * x } _ => 0 }", "6"),
+            ("let x = 1; match 5 { x => x }; x", "1"),
+            ("match 1 { 1 => {} }", "()"),
+            ("struct P { x }; match (P { x: 1 }) { p => p.x }", "1"),
+            (
+                "let out = []; for i in 0..4 { match i { 1 => continue, 3 => break, _ => out += [i] } }; out",
+                "[0, 2]",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, display)| {
+            let mut eval = Evaluator::new();
+            let results = process_string(&mut eval, string.to_string(), false).unwrap();
+
+            assert_eq!(
+                results.last().unwrap().as_ref().unwrap().to_string(),
+                display,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn match_errors() {
+        let shape = "enum Shape { Circle(r), Rect(w, h), Empty }; ";
+        [
+            (
+                "match Circle(1) { Circle(r) => r }",
+                "non-exhaustive match on Shape: missing Rect(w, h), Empty",
+            ),
+            (
+                "match Empty { Circle(r) if r > 0 => r, Rect(_, 1) => 1, Empty => 0 }",
+                "non-exhaustive match on Shape: missing Circle(r), Rect(w, h)",
+            ),
+            (
+                "fn area(s) match s { Circle(r) => r * r }",
+                "non-exhaustive match on Shape: missing Rect(w, h), Empty",
+            ),
+            (
+                "let f = fn() { let s = 1; match s { Empty => 0, Rect(w, h) => w * h } }",
+                "non-exhaustive match on Shape: missing Circle(r)",
+            ),
+            (
+                "match 5 { Circle(r) => r, Empty => 0 }",
+                "non-exhaustive match on Shape: missing Rect(w, h)",
+            ),
+            (
+                "match Empty { Circel(r) => r, _ => 0 }",
+                "type error: Circel is not a variant of Shape",
+            ),
+            (
+                "match Empty { Rect(w) => w, _ => 0 }",
+                "type error: Rect holds 2 values, but the pattern has 1",
+            ),
+            (
+                "match 5 { 0 => 1, 1 => 2 }",
+                "pattern mismatch: no arm matches 5",
+            ),
+            (
+                "match 5 { x if x > 9 => 1 }",
+                "pattern mismatch: no arm matches 5",
+            ),
+            ("Circle(1, 2)", "expected 1 arguments, found 2"),
+            ("Empty()", "type error: Empty is not a function"),
+            (
+                "let Circle(r) = Empty",
+                "pattern mismatch: expected `Circle` holding 1 value, found Empty",
+            ),
+            (
+                "enum Shape { A }; enum E { A, A }",
+                "syntax error: variant `A` is declared more than once",
+            ),
+            (
+                "enum shape { A }",
+                "syntax error: expected type name starting with an uppercase letter",
+            ),
+            ("match 1 { 1 => 1 2 => 2 }", "expected `}` to close `{`"),
+            ("for x in Empty { x }", "type error: Empty is not iterable"),
+            (
+                "for x in Shape { x }",
+                "type error: enum Shape { Circle(r), Rect(w, h), Empty } is not iterable",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(string, message)| {
+            let string = format!("{}{}", shape, string);
+            let mut eval = Evaluator::new();
+            let result = process_string(&mut eval, string.clone(), false);
+
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                message,
+                "expression = \"{}\"",
+                string
+            );
+        })
+    }
+
+    #[test]
+    fn match_checked_before_running() {
+        let mut eval = Evaluator::new();
+        process_string(&mut eval, "enum E { A, B }".into(), false).unwrap();
+
+        // The enum is found from an earlier input, and nothing runs if a match misses a variant
+        let result = process_string(
+            &mut eval,
+            "let x = 1; fn f(e) match e { A => 1 }".into(),
+            false,
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "non-exhaustive match on E: missing B"
+        );
+
+        let result = process_string(&mut eval, "x".into(), false);
+        assert_eq!(result.unwrap_err().to_string(), "unknown identifier: x");
     }
 }
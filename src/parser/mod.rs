@@ -2,108 +2,325 @@ use crate::core::*;
 use std::rc::Rc;
 
 pub struct Parser<'a> {
-    pub tokens: &'a [Token],
+    pub tokens: &'a [SpannedToken],
     pub position: usize,
+    errors: Vec<IntyError>,
+
+    /// Whether an uppercase name followed by braces is a record, which is not the case right
+    /// before the body of a statement or the arms of a match (e.g. `if x == Empty { ... }`)
+    records: bool,
 }
 
 impl<'a> Parser<'a> {
-    pub fn new(tokens: &'a [Token]) -> Self {
+    pub fn new(tokens: &'a [SpannedToken]) -> Self {
         Self {
             tokens,
             position: 0,
+            errors: Vec::new(),
+            records: true,
         }
     }
 
-    /// Parse a list of tokens into an AST
+    /// Parse a list of tokens into an AST, stopping at the first syntax error
     pub fn parse(&mut self) -> IntyResult<Vec<Stmt>> {
+        let (statements, mut errors) = self.parse_with_recovery();
+
+        match errors.is_empty() {
+            true => Ok(statements),
+            false => Err(errors.remove(0)),
+        }
+    }
+
+    /// Parse a list of tokens into an AST, skipping past syntax errors to collect all of them
+    pub fn parse_with_recovery(&mut self) -> (Vec<Stmt>, Vec<IntyError>) {
         let mut statements = Vec::new();
 
         while self.has_more_tokens() {
-            let statement = self.parse_stmt()?;
-            statements.push(statement);
+            let position = self.position;
+            let recovered = match self.parse_stmt() {
+                Ok(statement) => {
+                    statements.push(statement);
+                    false
+                }
+                Err(err) => {
+                    self.recover(err, position, false);
+                    true
+                }
+            };
+
+            // If there are more tokens remaining after a statement, they must be invalid.
+            // e.g. "2 + 3 4 + 5" will not have parsed "4 + 5"
+            if !recovered && !matches!(self.peek(), None | Some(Token::Semicolon)) {
+                self.errors.push(IntyError::InvalidExpressionError {
+                    message: "tokens remaining after parsing".to_string(),
+                    span: self.current_span(),
+                });
+                self.synchronize(false);
+            }
 
             // Check for semicolon to separate statements
-            if self.has_more_tokens() && self.clone_current()? == Token::Semicolon {
+            if self.peek() == Some(&Token::Semicolon) {
                 self.advance();
-            } else {
-                break;
             }
         }
 
-        // If there are more tokens remaining after a successful parse, they must be invalid.
-        // e.g. "2 + 3 4 + 5" will not have parsed "4 + 5"
-        if self.has_more_tokens() {
-            return Err(IntyError::InvalidExpressionError {
-                message: "tokens remaining after parsing".to_string(),
-            });
-        }
-
-        Ok(statements)
+        (statements, std::mem::take(&mut self.errors))
     }
 
     /// Parse a single statement
     fn parse_stmt(&mut self) -> IntyResult<Stmt> {
-        Ok(match self.clone_current()? {
-            Token::If => {
+        let start = self.current_span();
+
+        let kind = match self.clone_current()? {
+            Token::Let => {
                 self.advance();
+                let pattern = self.parse_pattern()?;
+                self.consume(Token::Equal)?;
 
-                Stmt::If {
-                    test: self.parse_or()?,
-                    branch: Rc::new(self.parse_stmt()?),
-                    else_branch: match self.peek() {
-                        Some(Token::Else) => {
-                            self.advance();
-                            Some(Rc::new(self.parse_stmt()?))
-                        }
-                        _ => None,
-                    },
+                StmtKind::Let {
+                    pattern,
+                    expr: self.parse_or()?,
                 }
             }
 
-            Token::Let => {
+            Token::Ident(ident) if matches!(self.peek_next(), Some(Token::Equal)) => {
+                self.advance();
+                self.consume(Token::Equal)?;
+
+                StmtKind::Assign {
+                    ident,
+                    expr: self.parse_or()?,
+                }
+            }
+
+            Token::Ident(ident)
+                if matches!(
+                    self.peek_next(),
+                    Some(
+                        Token::PlusEqual
+                            | Token::HyphenEqual
+                            | Token::StarEqual
+                            | Token::DivideEqual
+                            | Token::CaretEqual
+                    )
+                ) =>
+            {
+                self.advance();
+                let operator = self.clone_current()?;
+                self.advance();
+
+                // Compound assignment is sugar for `x = x <op> <expr>`
+                let rhs = self.parse_or()?;
+                StmtKind::Assign {
+                    ident: ident.clone(),
+                    expr: Expr::new(
+                        ExprKind::Binary {
+                            operator: operator.into(),
+                            lhs: Rc::new(Expr::new(ExprKind::Ident(ident), start)),
+                            rhs: Rc::new(rhs),
+                        },
+                        self.span_from(start),
+                    ),
+                }
+            }
+
+            Token::Fn if matches!(self.peek_next(), Some(Token::Ident(_))) => {
                 self.advance();
                 if let Token::Ident(ident) = self.clone_current()? {
                     self.advance();
-                    self.consume(Token::Equal)?;
 
-                    Stmt::Let {
+                    StmtKind::Fn {
                         ident,
-                        expr: self.parse_or()?,
+                        params: self.parse_params()?,
+                        body: Rc::new(self.parse_stmt()?),
                     }
                 } else {
                     return Err(IntyError::SyntaxError {
                         message: "expected identifier".to_string(),
+                        span: self.current_span(),
                     });
                 }
             }
 
-            Token::LeftBrace => {
+            Token::While => {
                 self.advance();
 
-                if !self.has_more_tokens() {
-                    return Err(IntyError::SyntaxError {
-                        message: "expected right brace".to_string(),
-                    });
+                StmtKind::While {
+                    test: self.parse_or_records(false)?,
+                    body: Rc::new(self.parse_stmt()?),
                 }
+            }
 
-                let mut stmts = vec![self.parse_stmt()?];
+            Token::For
+                if matches!(self.peek_next(), Some(Token::Ident(_)))
+                    && matches!(self.peek_nth(2), Some(Token::In)) =>
+            {
+                self.advance();
+                let ident = match self.clone_current()? {
+                    Token::Ident(ident) => ident,
+                    _ => unreachable!("identifier was checked above"),
+                };
+                self.advance();
+                self.consume(Token::In)?;
 
-                while let Some(next) = self.peek() {
-                    if next == &Token::Semicolon {
+                StmtKind::ForIn {
+                    ident,
+                    iter: self.parse_or_records(false)?,
+                    body: Rc::new(self.parse_stmt()?),
+                }
+            }
+
+            Token::For => {
+                self.advance();
+
+                // `for x = 0; ...` declares the loop variable rather than re-assigning it
+                let init_start = self.current_span();
+                let init = match (self.clone_current()?, self.peek_next()) {
+                    (Token::Ident(ident), Some(Token::Equal)) => {
                         self.advance();
-                        stmts.push(self.parse_stmt()?);
-                    } else {
+                        self.advance();
+
+                        let kind = StmtKind::Let {
+                            pattern: Pattern::ident(ident, init_start),
+                            expr: self.parse_or()?,
+                        };
+                        Stmt::new(kind, self.span_from(init_start))
+                    }
+                    _ => self.parse_stmt()?,
+                };
+                self.consume(Token::Semicolon)?;
+
+                let test = self.parse_or_records(false)?;
+                self.consume(Token::Semicolon)?;
+
+                StmtKind::For {
+                    init: Rc::new(init),
+                    test,
+                    update: Rc::new(self.with_records(false, Self::parse_stmt)?),
+                    body: Rc::new(self.parse_stmt()?),
+                }
+            }
+
+            Token::Break => {
+                self.advance();
+                StmtKind::Break
+            }
+
+            Token::Continue => {
+                self.advance();
+                StmtKind::Continue
+            }
+
+            Token::Struct => {
+                self.advance();
+
+                let ident = self.parse_type_name()?;
+                let open = self.current_span();
+                self.consume(Token::LeftBrace)?;
+
+                let mut fields: Vec<String> = Vec::new();
+                while let Some(Token::Ident(field)) = self.peek().cloned() {
+                    if fields.contains(&field) {
+                        return Err(IntyError::SyntaxError {
+                            message: format!("field `{}` is declared more than once", field),
+                            span: self.current_span(),
+                        });
+                    }
+
+                    fields.push(field);
+                    self.advance();
+
+                    if self.peek() != Some(&Token::Comma) {
                         break;
                     }
+                    self.advance();
                 }
 
-                self.consume(Token::RightBrace)?;
+                self.consume_closing(Token::LeftBrace, open)?;
 
-                Stmt::Block(stmts)
+                StmtKind::Struct { ident, fields }
             }
 
-            _ => Stmt::Expr(self.parse_or()?),
-        })
+            Token::Enum => {
+                self.advance();
+
+                let ident = self.parse_type_name()?;
+                let open = self.current_span();
+                self.consume(Token::LeftBrace)?;
+
+                let mut variants: Vec<(String, Vec<String>)> = Vec::new();
+                while let Some(Token::Ident(_)) = self.peek() {
+                    let variant_start = self.current_span();
+                    let variant = self.parse_type_name()?;
+
+                    if variants.iter().any(|(other, _)| *other == variant) {
+                        return Err(IntyError::SyntaxError {
+                            message: format!("variant `{}` is declared more than once", variant),
+                            span: variant_start,
+                        });
+                    }
+
+                    let fields = match self.peek() {
+                        Some(Token::LeftParen) => self.parse_params()?,
+                        _ => Vec::new(),
+                    };
+                    variants.push((variant, fields));
+
+                    if self.peek() != Some(&Token::Comma) {
+                        break;
+                    }
+                    self.advance();
+                }
+
+                self.consume_closing(Token::LeftBrace, open)?;
+
+                StmtKind::Enum { ident, variants }
+            }
+
+            Token::Delete => {
+                self.advance();
+
+                let (ident, path) = self.parse_index_path()?;
+                StmtKind::Delete { ident, path }
+            }
+
+            _ => {
+                let expr = self.parse_or()?;
+
+                match self.peek() {
+                    Some(Token::Equal) => {
+                        let (ident, path) = Self::index_path(expr)?;
+                        self.advance();
+
+                        StmtKind::SetIndex {
+                            ident,
+                            path,
+                            expr: self.parse_or()?,
+                        }
+                    }
+                    _ => StmtKind::Expr(expr),
+                }
+            }
+        };
+
+        Ok(Stmt::new(kind, self.span_from(start)))
+    }
+
+    /// Parse with record literals allowed or not, restoring the previous setting afterwards
+    fn with_records<T>(
+        &mut self,
+        records: bool,
+        parse: impl FnOnce(&mut Self) -> IntyResult<T>,
+    ) -> IntyResult<T> {
+        let outer = std::mem::replace(&mut self.records, records);
+        let result = parse(self);
+        self.records = outer;
+        result
+    }
+
+    /// Parse an expression, allowing record literals in it or not
+    fn parse_or_records(&mut self, records: bool) -> IntyResult<Expr> {
+        self.with_records(records, Self::parse_or)
     }
 
     fn parse_or(&mut self) -> IntyResult<Expr> {
@@ -116,11 +333,15 @@ impl<'a> Parser<'a> {
                     self.advance();
                     let rhs = self.parse_and()?;
 
-                    lhs = Expr::Logical {
-                        operator: operator.into(),
-                        lhs: Rc::new(lhs),
-                        rhs: Rc::new(rhs),
-                    };
+                    let span = lhs.span.to(rhs.span);
+                    lhs = Expr::new(
+                        ExprKind::Logical {
+                            operator: operator.into(),
+                            lhs: Rc::new(lhs),
+                            rhs: Rc::new(rhs),
+                        },
+                        span,
+                    );
                 }
 
                 _ => break,
@@ -140,11 +361,15 @@ impl<'a> Parser<'a> {
                     self.advance();
                     let rhs = self.parse_rel()?;
 
-                    lhs = Expr::Logical {
-                        operator: operator.into(),
-                        lhs: Rc::new(lhs),
-                        rhs: Rc::new(rhs),
-                    };
+                    let span = lhs.span.to(rhs.span);
+                    lhs = Expr::new(
+                        ExprKind::Logical {
+                            operator: operator.into(),
+                            lhs: Rc::new(lhs),
+                            rhs: Rc::new(rhs),
+                        },
+                        span,
+                    );
                 }
 
                 _ => break,
@@ -155,7 +380,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_rel(&mut self) -> IntyResult<Expr> {
-        let mut lhs = self.parse_expr()?;
+        let mut lhs = self.parse_range()?;
 
         while self.has_more_tokens() {
             let operator = self.clone_current()?;
@@ -165,15 +390,20 @@ impl<'a> Parser<'a> {
                 | Token::RelGt
                 | Token::RelLt
                 | Token::RelGte
-                | Token::RelLte => {
+                | Token::RelLte
+                | Token::In => {
                     self.advance();
-                    let rhs = self.parse_expr()?;
-
-                    lhs = Expr::Relational {
-                        operator: operator.into(),
-                        lhs: Rc::new(lhs),
-                        rhs: Rc::new(rhs),
-                    };
+                    let rhs = self.parse_range()?;
+
+                    let span = lhs.span.to(rhs.span);
+                    lhs = Expr::new(
+                        ExprKind::Relational {
+                            operator: operator.into(),
+                            lhs: Rc::new(lhs),
+                            rhs: Rc::new(rhs),
+                        },
+                        span,
+                    );
                 }
 
                 _ => break,
@@ -183,6 +413,81 @@ impl<'a> Parser<'a> {
         Ok(lhs)
     }
 
+    fn parse_range(&mut self) -> IntyResult<Expr> {
+        let start = self.parse_bit_or()?;
+
+        let inclusive = match self.peek() {
+            Some(Token::DotDot) => false,
+            Some(Token::DotDotEqual) => true,
+            _ => return Ok(start),
+        };
+        self.advance();
+
+        let end = self.parse_bit_or()?;
+
+        let step = match self.peek() {
+            Some(Token::Step) => {
+                self.advance();
+                Some(Rc::new(self.parse_bit_or()?))
+            }
+            _ => None,
+        };
+
+        let span = self.span_from(start.span);
+        Ok(Expr::new(
+            ExprKind::Range {
+                start: Rc::new(start),
+                end: Rc::new(end),
+                step,
+                inclusive,
+            },
+            span,
+        ))
+    }
+
+    fn parse_bit_or(&mut self) -> IntyResult<Expr> {
+        self.parse_bitwise(&[Token::Pipe], Self::parse_bit_xor)
+    }
+
+    fn parse_bit_xor(&mut self) -> IntyResult<Expr> {
+        self.parse_bitwise(&[Token::CaretCaret], Self::parse_bit_and)
+    }
+
+    fn parse_bit_and(&mut self) -> IntyResult<Expr> {
+        self.parse_bitwise(&[Token::Ampersand], Self::parse_shift)
+    }
+
+    fn parse_shift(&mut self) -> IntyResult<Expr> {
+        self.parse_bitwise(&[Token::ShiftLeft, Token::ShiftRight], Self::parse_expr)
+    }
+
+    /// Parse a left-associative chain of bitwise operators, with operands parsed by `next`
+    fn parse_bitwise(
+        &mut self,
+        operators: &[Token],
+        next: fn(&mut Self) -> IntyResult<Expr>,
+    ) -> IntyResult<Expr> {
+        let mut lhs = next(self)?;
+
+        while let Some(operator) = self.peek().filter(|token| operators.contains(token)) {
+            let operator = operator.clone();
+            self.advance();
+            let rhs = next(self)?;
+
+            let span = lhs.span.to(rhs.span);
+            lhs = Expr::new(
+                ExprKind::Bitwise {
+                    operator: operator.into(),
+                    lhs: Rc::new(lhs),
+                    rhs: Rc::new(rhs),
+                },
+                span,
+            );
+        }
+
+        Ok(lhs)
+    }
+
     fn parse_expr(&mut self) -> IntyResult<Expr> {
         let mut lhs = self.parse_mult()?;
 
@@ -193,11 +498,15 @@ impl<'a> Parser<'a> {
                     self.advance();
                     let rhs = self.parse_mult()?;
 
-                    lhs = Expr::Binary {
-                        operator: operator.into(),
-                        lhs: Rc::new(lhs),
-                        rhs: Rc::new(rhs),
-                    };
+                    let span = lhs.span.to(rhs.span);
+                    lhs = Expr::new(
+                        ExprKind::Binary {
+                            operator: operator.into(),
+                            lhs: Rc::new(lhs),
+                            rhs: Rc::new(rhs),
+                        },
+                        span,
+                    );
                 }
 
                 _ => break,
@@ -213,15 +522,24 @@ impl<'a> Parser<'a> {
         while self.has_more_tokens() {
             let operator = self.clone_current()?;
             match operator {
-                Token::Star | Token::Divide => {
+                Token::Star
+                | Token::Divide
+                | Token::DivideDot
+                | Token::DivideDivide
+                | Token::Percent
+                | Token::Mod => {
                     self.advance();
                     let rhs = self.parse_pow()?;
 
-                    lhs = Expr::Binary {
-                        operator: operator.into(),
-                        lhs: Rc::new(lhs),
-                        rhs: Rc::new(rhs),
-                    };
+                    let span = lhs.span.to(rhs.span);
+                    lhs = Expr::new(
+                        ExprKind::Binary {
+                            operator: operator.into(),
+                            lhs: Rc::new(lhs),
+                            rhs: Rc::new(rhs),
+                        },
+                        span,
+                    );
                 }
 
                 _ => break,
@@ -232,7 +550,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_pow(&mut self) -> IntyResult<Expr> {
-        let lhs = self.parse_unary()?;
+        let lhs = self.parse_postfix()?;
 
         if !self.has_more_tokens() {
             return Ok(lhs);
@@ -244,121 +562,823 @@ impl<'a> Parser<'a> {
                 self.advance();
                 let rhs = self.parse_pow()?;
 
-                Ok(Expr::Binary {
-                    operator: operator.into(),
-                    lhs: Rc::new(lhs),
-                    rhs: Rc::new(rhs),
-                })
+                let span = lhs.span.to(rhs.span);
+                Ok(Expr::new(
+                    ExprKind::Binary {
+                        operator: operator.into(),
+                        lhs: Rc::new(lhs),
+                        rhs: Rc::new(rhs),
+                    },
+                    span,
+                ))
             }
 
             _ => Ok(lhs),
         }
     }
 
+    fn parse_postfix(&mut self) -> IntyResult<Expr> {
+        let mut expr = self.parse_unary()?;
+
+        while let Some(token) = self.peek() {
+            match token {
+                Token::LeftParen => {
+                    let open = self.current_span();
+                    self.advance();
+
+                    let mut args = Vec::new();
+
+                    while let Some(next) = self.peek() {
+                        match &next {
+                            Token::Comma => self.advance(),
+                            Token::RightParen => break,
+                            _ => args.push(self.parse_or_records(true)?),
+                        }
+                    }
+
+                    self.consume_closing(Token::LeftParen, open)?;
+
+                    let span = self.span_from(expr.span);
+                    expr = Expr::new(
+                        ExprKind::Call {
+                            callee: Rc::new(expr),
+                            args,
+                        },
+                        span,
+                    );
+                }
+
+                Token::Dot => {
+                    self.advance();
+
+                    let field = match self.clone_current()? {
+                        Token::Ident(field) => field,
+                        token => {
+                            return Err(IntyError::SyntaxTokenError {
+                                token,
+                                message: "expected field name, found".to_string(),
+                                span: self.current_span(),
+                            })
+                        }
+                    };
+                    self.advance();
+
+                    let span = self.span_from(expr.span);
+                    expr = Expr::new(
+                        ExprKind::Field {
+                            target: Rc::new(expr),
+                            field,
+                        },
+                        span,
+                    );
+                }
+
+                Token::LeftBracket => {
+                    let open = self.current_span();
+                    self.advance();
+
+                    // Either bound of a slice may be left out (e.g. `xs[1:]`, `xs[:2]`)
+                    let start = match self.peek() {
+                        Some(Token::Colon) => None,
+                        _ => Some(self.parse_or_records(true)?),
+                    };
+
+                    let target = Rc::new(expr);
+                    let kind = match (start, matches!(self.peek(), Some(Token::Colon))) {
+                        (start, true) => {
+                            self.advance();
+
+                            let end = match self.peek() {
+                                Some(Token::RightBracket) => None,
+                                _ => Some(self.parse_or_records(true)?),
+                            };
+
+                            ExprKind::Slice {
+                                target: target.clone(),
+                                start: start.map(Rc::new),
+                                end: end.map(Rc::new),
+                            }
+                        }
+                        (Some(index), false) => ExprKind::Index {
+                            target: target.clone(),
+                            index: Rc::new(index),
+                        },
+                        (None, false) => unreachable!("a missing start is always followed by `:`"),
+                    };
+
+                    self.consume_closing(Token::LeftBracket, open)?;
+
+                    expr = Expr::new(kind, self.span_from(target.span));
+                }
+
+                Token::PlusPlus | Token::HyphenHyphen => {
+                    let ExprKind::Ident(ident) = expr.kind else {
+                        return Err(IntyError::SyntaxTokenError {
+                            token: token.clone(),
+                            message: "expected identifier before".to_string(),
+                            span: self.current_span(),
+                        });
+                    };
+
+                    let operator = token.clone().into();
+                    self.advance();
+
+                    expr = Expr::new(
+                        ExprKind::Update {
+                            operator,
+                            ident,
+                            prefix: false,
+                        },
+                        self.span_from(expr.span),
+                    );
+                }
+
+                _ => break,
+            }
+        }
+
+        Ok(expr)
+    }
+
     fn parse_unary(&mut self) -> IntyResult<Expr> {
+        let start = self.current_span();
         let token = self.clone_current()?;
-        Ok(match token {
+
+        let kind = match token {
             Token::Integer(value) => {
                 self.advance();
-                Expr::Integer(value)
+                ExprKind::Integer(value)
+            }
+
+            Token::Float(value) => {
+                self.advance();
+                ExprKind::Float(value)
+            }
+
+            Token::Str(value) => {
+                self.advance();
+                ExprKind::Str(value)
+            }
+
+            Token::Interpolated(parts) => {
+                self.advance();
+
+                ExprKind::Interpolate(
+                    parts
+                        .into_iter()
+                        .map(|part| self.parse_interpolate_part(part, start))
+                        .collect::<IntyResult<_>>()?,
+                )
+            }
+
+            // An uppercase name followed by braces builds a record (e.g. `Point { x: 1, y: 2 }`)
+            Token::Ident(ident)
+                if self.records
+                    && Self::is_type_name(&ident)
+                    && self.peek_next() == Some(&Token::LeftBrace) =>
+            {
+                self.advance();
+                let open = self.current_span();
+                self.advance();
+
+                let mut fields = Vec::new();
+                let mut base = None;
+
+                while self.peek() != Some(&Token::RightBrace) {
+                    // The remaining fields can be copied from another record at the end
+                    if self.peek() == Some(&Token::DotDot) {
+                        self.advance();
+                        base = Some(Rc::new(self.parse_or()?));
+                        break;
+                    }
+
+                    let field_start = self.current_span();
+                    let field = match self.clone_current()? {
+                        Token::Ident(field) => field,
+                        token => {
+                            return Err(IntyError::SyntaxTokenError {
+                                token,
+                                message: "expected field name, found".to_string(),
+                                span: field_start,
+                            })
+                        }
+                    };
+                    self.advance();
+
+                    // A field without a value takes the variable of the same name
+                    let value = match self.peek() {
+                        Some(Token::Colon) => {
+                            self.advance();
+                            self.parse_or()?
+                        }
+                        _ => Expr::new(ExprKind::Ident(field.clone()), field_start),
+                    };
+                    fields.push((field, value));
+
+                    if self.peek() != Some(&Token::Comma) {
+                        break;
+                    }
+                    self.advance();
+                }
+
+                self.consume_closing(Token::LeftBrace, open)?;
+
+                ExprKind::Record {
+                    ident,
+                    fields,
+                    base,
+                }
             }
 
             Token::Ident(ident) => {
                 self.advance();
-                Expr::Ident(ident)
+                ExprKind::Ident(ident)
             }
 
             Token::True => {
                 self.advance();
-                Expr::Bool(true)
+                ExprKind::Bool(true)
             }
 
             Token::False => {
                 self.advance();
-                Expr::Bool(false)
+                ExprKind::Bool(false)
             }
 
-            Token::Bang => {
+            Token::Bang | Token::Tilde => {
                 self.advance();
 
-                Expr::Unary {
+                ExprKind::Unary {
                     operator: UnOp::from(token),
-                    value: Rc::new(self.parse_unary()?),
+                    value: Rc::new(self.parse_postfix()?),
+                }
+            }
+
+            Token::PlusPlus | Token::HyphenHyphen => {
+                self.advance();
+
+                if let Token::Ident(ident) = self.clone_current()? {
+                    self.advance();
+
+                    ExprKind::Update {
+                        operator: UpdOp::from(token),
+                        ident,
+                        prefix: true,
+                    }
+                } else {
+                    return Err(IntyError::SyntaxError {
+                        message: format!("expected identifier after {}", token),
+                        span: self.current_span(),
+                    });
                 }
             }
 
             Token::Hyphen | Token::Plus => {
                 self.advance();
 
-                Expr::Unary {
-                    operator: UnOp::from(token),
-                    value: Rc::new(self.parse_pow()?),
+                ExprKind::Unary {
+                    operator: UnOp::from(token),
+                    value: Rc::new(self.parse_pow()?),
+                }
+            }
+
+            Token::If => {
+                self.advance();
+
+                ExprKind::If {
+                    test: Rc::new(self.parse_or_records(false)?),
+                    branch: Rc::new(self.parse_stmt()?),
+                    else_branch: match self.peek() {
+                        Some(Token::Else) => {
+                            self.advance();
+                            Some(Rc::new(self.parse_stmt()?))
+                        }
+                        _ => None,
+                    },
+                }
+            }
+
+            Token::Match => {
+                self.advance();
+
+                let value = Rc::new(self.parse_or_records(false)?);
+                let open = self.current_span();
+                self.consume(Token::LeftBrace)?;
+
+                let mut arms = Vec::new();
+                while self.has_more_tokens() && self.peek() != Some(&Token::RightBrace) {
+                    let pattern = self.parse_pattern()?;
+                    let guard = match self.peek() {
+                        Some(Token::If) => {
+                            self.advance();
+                            Some(self.parse_or()?)
+                        }
+                        _ => None,
+                    };
+
+                    self.consume(Token::FatArrow)?;
+                    let body = self.parse_stmt()?;
+                    arms.push(MatchArm {
+                        pattern,
+                        guard,
+                        body,
+                    });
+
+                    // Arms are separated by commas, which can be left out after a block
+                    let after_block = self.tokens[self.position - 1].token == Token::RightBrace;
+                    match self.peek() {
+                        Some(Token::Comma) => self.advance(),
+                        Some(Token::RightBrace) => {}
+                        _ if after_block => {}
+                        _ => break,
+                    }
+                }
+
+                self.consume_closing(Token::LeftBrace, open)?;
+
+                ExprKind::Match { value, arms }
+            }
+
+            Token::LeftBrace => {
+                self.advance();
+
+                if !self.has_more_tokens() {
+                    return Err(self.unclosed(Token::LeftBrace, start));
+                }
+
+                self.with_records(true, |parser| parser.parse_braces(start))?
+            }
+
+            Token::Fn => {
+                self.advance();
+
+                ExprKind::Lambda {
+                    params: self.parse_params()?,
+                    body: Rc::new(self.parse_stmt()?),
+                }
+            }
+
+            Token::LeftParen => {
+                self.advance();
+
+                if !self.has_more_tokens() {
+                    return Err(self.unclosed(Token::LeftParen, start));
+                }
+
+                let expr = self.parse_or_records(true)?;
+
+                // A comma after the first expression makes this a tuple (e.g. `(1, 2)`, `(1,)`)
+                if self.peek() == Some(&Token::Comma) {
+                    let mut items = vec![expr];
+
+                    while self.peek() == Some(&Token::Comma) {
+                        self.advance();
+
+                        if self.peek() == Some(&Token::RightParen) {
+                            break;
+                        }
+                        items.push(self.parse_or_records(true)?);
+                    }
+
+                    self.consume_closing(Token::LeftParen, start)?;
+                    ExprKind::Tuple(items)
+                } else {
+                    self.consume_closing(Token::LeftParen, start)?;
+
+                    // The parentheses are included in the span of the inner expression
+                    return Ok(Expr::new(expr.kind, self.span_from(start)));
+                }
+            }
+
+            Token::LeftBracket => {
+                self.advance();
+
+                if !self.has_more_tokens() {
+                    return Err(self.unclosed(Token::LeftBracket, start));
+                }
+
+                let mut values = Vec::new();
+
+                while let Some(next) = self.peek() {
+                    match &next {
+                        Token::Comma => self.advance(),
+                        Token::RightBracket => break,
+                        Token::DotDotDot => {
+                            let spread_start = self.current_span();
+                            self.advance();
+
+                            let spread = ExprKind::Spread(Rc::new(self.parse_or_records(true)?));
+                            values.push(Expr::new(spread, self.span_from(spread_start)));
+                        }
+                        _ => values.push(self.parse_or_records(true)?),
+                    }
+                }
+
+                self.consume_closing(Token::LeftBracket, start)?;
+
+                ExprKind::List(values)
+            }
+
+            _ => {
+                return Err(IntyError::SyntaxTokenError {
+                    token,
+                    message: "unexpected token".to_string(),
+                    span: start,
+                })
+            }
+        };
+
+        Ok(Expr::new(kind, self.span_from(start)))
+    }
+
+    /// Parse a piece of an interpolated string, where embedded tokens must form a single expression
+    fn parse_interpolate_part(
+        &mut self,
+        part: StrPart,
+        start: Span,
+    ) -> IntyResult<InterpolatePart> {
+        let tokens = match part {
+            StrPart::Literal(value) => return Ok(InterpolatePart::Literal(value)),
+            StrPart::Tokens(tokens) => tokens,
+        };
+
+        if tokens.is_empty() {
+            return Err(IntyError::SyntaxError {
+                message: "expected expression in string interpolation".to_string(),
+                span: start,
+            });
+        }
+
+        let mut parser = Parser::new(&tokens);
+        let expr = parser.parse_or();
+
+        // Errors recovered from inside the interpolation still belong to this parse
+        self.errors.append(&mut parser.errors);
+        let expr = expr?;
+
+        if parser.has_more_tokens() {
+            return Err(IntyError::InvalidExpressionError {
+                message: "tokens remaining in string interpolation".to_string(),
+                span: parser.current_span(),
+            });
+        }
+
+        Ok(InterpolatePart::Expr(expr))
+    }
+
+    /// Parse the inside of braces after the opening `{`, which is either a block of statements
+    /// or a map literal. A map is told apart by a `:` after its first key, which can never
+    /// follow a statement.
+    fn parse_braces(&mut self, start: Span) -> IntyResult<ExprKind> {
+        // `{}` is an empty block, so the empty map is written `{:}`
+        if self.peek() == Some(&Token::RightBrace) {
+            self.advance();
+            return Ok(ExprKind::Block(vec![]));
+        }
+        if self.peek() == Some(&Token::Colon) && self.peek_next() == Some(&Token::RightBrace) {
+            self.advance();
+            self.advance();
+            return Ok(ExprKind::Map(vec![]));
+        }
+
+        let mut stmts = Vec::new();
+
+        loop {
+            let position = self.position;
+            let recovered = match self.parse_stmt() {
+                Ok(Stmt {
+                    kind: StmtKind::Expr(key),
+                    ..
+                }) if stmts.is_empty() && self.peek() == Some(&Token::Colon) => {
+                    return self.parse_map(key, start);
+                }
+                Ok(stmt) => {
+                    stmts.push(stmt);
+                    false
+                }
+                Err(err) => {
+                    self.recover(err, position, true);
+                    true
+                }
+            };
+
+            match self.peek() {
+                Some(Token::Semicolon) => self.advance(),
+                Some(Token::RightBrace) => break,
+                Some(_) if recovered => {}
+                _ => break,
+            }
+        }
+
+        self.consume_closing(Token::LeftBrace, start)?;
+
+        Ok(ExprKind::Block(stmts))
+    }
+
+    /// Parse the entries of a map literal (e.g. `{ "a": 1, "b": 2 }`), given its first key
+    fn parse_map(&mut self, first: Expr, start: Span) -> IntyResult<ExprKind> {
+        let mut entries = Vec::new();
+        let mut key = first;
+
+        loop {
+            self.consume(Token::Colon)?;
+            entries.push((key, self.parse_or()?));
+
+            if self.peek() != Some(&Token::Comma) {
+                break;
+            }
+            self.advance();
+
+            // A trailing comma is allowed before the closing brace
+            if self.peek() == Some(&Token::RightBrace) {
+                break;
+            }
+            key = self.parse_or()?;
+        }
+
+        self.consume_closing(Token::LeftBrace, start)?;
+
+        Ok(ExprKind::Map(entries))
+    }
+
+    /// Parse an indexed variable (e.g. `m["a"][0]`) into the variable and its indices
+    fn parse_index_path(&mut self) -> IntyResult<(String, Vec<Rc<Expr>>)> {
+        let expr = self.parse_postfix()?;
+        Self::index_path(expr)
+    }
+
+    /// Split an indexed variable into the variable and its indices, outermost first
+    fn index_path(expr: Expr) -> IntyResult<(String, Vec<Rc<Expr>>)> {
+        let span = expr.span;
+        let mut path = Vec::new();
+        let mut current = Rc::new(expr);
+
+        loop {
+            match &current.kind {
+                ExprKind::Index { target, index } => {
+                    path.push(index.clone());
+                    current = target.clone();
+                }
+                ExprKind::Ident(ident) if !path.is_empty() => {
+                    path.reverse();
+                    return Ok((ident.clone(), path));
+                }
+                _ => {
+                    return Err(IntyError::SyntaxError {
+                        message: "expected an indexed variable (e.g. `xs[0]`)".to_string(),
+                        span,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Parse a pattern to destructure a value into (e.g. `x`, `(a, _)`, `[head, ...rest]`)
+    fn parse_pattern(&mut self) -> IntyResult<Pattern> {
+        let start = self.current_span();
+
+        let kind = match self.clone_current()? {
+            // An uppercase name refers to a variant of an enum rather than binding a new name
+            Token::Ident(ident) if Self::is_type_name(&ident) => {
+                self.advance();
+
+                let mut fields = Vec::new();
+                if self.peek() == Some(&Token::LeftParen) {
+                    let open = self.current_span();
+                    self.advance();
+
+                    while self.peek() != Some(&Token::RightParen) {
+                        fields.push(self.parse_pattern()?);
+
+                        if self.peek() != Some(&Token::Comma) {
+                            break;
+                        }
+                        self.advance();
+                    }
+
+                    self.consume_closing(Token::LeftParen, open)?;
+                }
+
+                PatternKind::Variant { ident, fields }
+            }
+
+            Token::Ident(ident) => {
+                self.advance();
+
+                match ident.as_str() {
+                    "_" => PatternKind::Wildcard,
+                    _ => PatternKind::Ident(ident),
                 }
             }
 
             Token::LeftParen => {
                 self.advance();
 
-                if !self.has_more_tokens() {
-                    return Err(IntyError::SyntaxError {
-                        message: "expected right parenthesis".to_string(),
-                    });
+                let mut items = Vec::new();
+                let mut tuple = false;
+
+                while self.peek() != Some(&Token::RightParen) {
+                    items.push(self.parse_pattern()?);
+
+                    if self.peek() != Some(&Token::Comma) {
+                        break;
+                    }
+                    self.advance();
+                    tuple = true;
                 }
 
-                let expr = self.parse_or()?;
-                self.consume(Token::RightParen)?;
+                self.consume_closing(Token::LeftParen, start)?;
 
-                expr
+                // Without a comma the parentheses only group a single pattern
+                match (tuple, items.len()) {
+                    (false, 1) => items.pop().expect("there is one item").kind,
+                    _ => PatternKind::Tuple(items),
+                }
             }
 
             Token::LeftBracket => {
                 self.advance();
 
-                if !self.has_more_tokens() {
-                    return Err(IntyError::SyntaxError {
-                        message: "expected right bracket".to_string(),
-                    });
-                }
+                let mut items = Vec::new();
+                let mut rest = None;
 
-                let mut values = Vec::new();
+                while self.peek() != Some(&Token::RightBracket) {
+                    // The rest of the list can only be bound at the end (e.g. `[a, ...rest]`)
+                    if self.peek() == Some(&Token::DotDotDot) {
+                        let rest_start = self.current_span();
+                        self.advance();
 
-                while let Some(next) = self.peek() {
-                    match &next {
-                        Token::Comma => self.advance(),
-                        Token::RightBracket => break,
-                        _ => values.push(self.parse_or()?),
+                        rest = Some(Rc::new(match self.peek() {
+                            Some(Token::Ident(_)) => self.parse_pattern()?,
+                            _ => Pattern::new(PatternKind::Wildcard, rest_start),
+                        }));
+                        break;
+                    }
+
+                    items.push(self.parse_pattern()?);
+
+                    if self.peek() != Some(&Token::Comma) {
+                        break;
                     }
+                    self.advance();
                 }
 
-                self.consume(Token::RightBracket)?;
+                self.consume_closing(Token::LeftBracket, start)?;
 
-                Expr::List(values)
+                PatternKind::List { items, rest }
             }
 
-            _ => {
+            Token::Integer(val) => {
+                self.advance();
+                PatternKind::Literal(Value::Integer(val))
+            }
+            Token::Float(val) => {
+                self.advance();
+                PatternKind::Literal(Value::Float(val))
+            }
+            Token::Str(val) => {
+                self.advance();
+                PatternKind::Literal(Value::Str(val))
+            }
+            Token::True | Token::False => {
+                let val = self.clone_current()? == Token::True;
+                self.advance();
+                PatternKind::Literal(Value::Bool(val))
+            }
+
+            // Negative numbers are the only literals made of more than one token
+            Token::Hyphen => {
+                self.advance();
+
+                match self.clone_current()? {
+                    Token::Integer(val) => {
+                        self.advance();
+                        PatternKind::Literal(Value::Integer(-val))
+                    }
+                    Token::Float(val) => {
+                        self.advance();
+                        PatternKind::Literal(Value::Float(-val))
+                    }
+                    token => {
+                        return Err(IntyError::SyntaxTokenError {
+                            token,
+                            message: "expected number after `-` in pattern, found".to_string(),
+                            span: self.current_span(),
+                        })
+                    }
+                }
+            }
+
+            token => {
                 return Err(IntyError::SyntaxTokenError {
                     token,
-                    message: "unexpected token".to_string(),
+                    message: "expected pattern, found".to_string(),
+                    span: start,
                 })
             }
-        })
+        };
+
+        Ok(Pattern::new(kind, self.span_from(start)))
+    }
+
+    /// Check if an identifier names a type, which always start with an uppercase letter
+    fn is_type_name(ident: &str) -> bool {
+        ident.starts_with(|ch: char| ch.is_ascii_uppercase())
+    }
+
+    /// Parse the name of a type being declared (e.g. `Point`)
+    fn parse_type_name(&mut self) -> IntyResult<String> {
+        match self.clone_current()? {
+            Token::Ident(ident) if Self::is_type_name(&ident) => {
+                self.advance();
+                Ok(ident)
+            }
+            _ => Err(IntyError::SyntaxError {
+                message: "expected type name starting with an uppercase letter".to_string(),
+                span: self.current_span(),
+            }),
+        }
+    }
+
+    /// Parse a parenthesized list of parameter names (e.g. `(x, y)`)
+    fn parse_params(&mut self) -> IntyResult<Vec<String>> {
+        let open = self.current_span();
+        self.consume(Token::LeftParen)?;
+
+        let mut params = Vec::new();
+
+        while let Some(next) = self.peek() {
+            match next {
+                Token::Comma => self.advance(),
+                Token::RightParen => break,
+                Token::Ident(ident) => {
+                    params.push(ident.clone());
+                    self.advance();
+                }
+                _ => {
+                    return Err(IntyError::SyntaxTokenError {
+                        token: next.clone(),
+                        message: "expected parameter name, found".to_string(),
+                        span: self.current_span(),
+                    })
+                }
+            }
+        }
+
+        self.consume_closing(Token::LeftParen, open)?;
+
+        Ok(params)
     }
 
     /// Get a cloned instance of the current token
     fn clone_current(&self) -> IntyResult<Token> {
         if !self.has_more_tokens() {
             return Err(IntyError::SyntaxTokenError {
-                token: self.tokens[self.position - 1].clone(),
-                message: "unexpected token".to_string(),
+                token: self.tokens[self.position - 1].token.clone(),
+                message: "unexpected end of input after".to_string(),
+                span: self.current_span(),
             });
         }
 
-        Ok(self.tokens[self.position].clone())
+        Ok(self.tokens[self.position].token.clone())
     }
 
     /// Get the next token if it exists, but do not advance
     fn peek(&self) -> Option<&Token> {
-        self.has_more_tokens().then(|| &self.tokens[self.position])
+        self.peek_nth(0)
+    }
+
+    /// Get the token after the current one if it exists, but do not advance
+    fn peek_next(&self) -> Option<&Token> {
+        self.peek_nth(1)
+    }
+
+    /// Get the token `n` places after the current one if it exists, but do not advance
+    fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.tokens
+            .get(self.position + n)
+            .map(|spanned| &spanned.token)
+    }
+
+    /// Get the span of the current token, or the end of the input if there are none left
+    fn current_span(&self) -> Span {
+        match (self.tokens.get(self.position), self.tokens.last()) {
+            (Some(current), _) => current.span,
+            (None, Some(last)) => last.span.after(),
+            (None, None) => Span::default(),
+        }
+    }
+
+    /// Get a span from the start of a node up to the end of the last consumed token
+    fn span_from(&self, start: Span) -> Span {
+        match self
+            .position
+            .checked_sub(1)
+            .and_then(|i| self.tokens.get(i))
+        {
+            Some(last) => start.to(last.span),
+            None => start,
+        }
     }
 
     /// Move onto the next token
@@ -366,6 +1386,45 @@ impl<'a> Parser<'a> {
         self.position += 1;
     }
 
+    /// Record a syntax error and skip ahead to where parsing can safely resume
+    fn recover(&mut self, err: IntyError, position: usize, in_block: bool) {
+        self.errors.push(err);
+
+        // Make sure the offending token is skipped so parsing can't get stuck on it
+        if self.position == position && !(in_block && self.peek() == Some(&Token::RightBrace)) {
+            self.advance();
+        }
+
+        self.synchronize(in_block);
+    }
+
+    /// Skip tokens until the next statement boundary: a `;`, a `}` or a keyword starting a statement
+    fn synchronize(&mut self, in_block: bool) {
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Semicolon
+                | Token::Let
+                | Token::Fn
+                | Token::While
+                | Token::For
+                | Token::Break
+                | Token::Continue
+                | Token::Delete
+                | Token::Struct
+                | Token::Enum => return,
+
+                // A closing brace ends the enclosing block, so only skip it at the top level
+                Token::RightBrace if in_block => return,
+                Token::RightBrace => {
+                    self.advance();
+                    return;
+                }
+
+                _ => self.advance(),
+            }
+        }
+    }
+
     /// Are there still tokens remaining?
     fn has_more_tokens(&self) -> bool {
         self.position < self.tokens.len()
@@ -380,7 +1439,40 @@ impl<'a> Parser<'a> {
             return Ok(found);
         }
 
-        return Err(IntyError::ExpectedTokenError { expected, found });
+        Err(IntyError::ExpectedTokenError {
+            expected,
+            found,
+            span: self.current_span(),
+        })
+    }
+
+    /// Match the current token to the delimiter closing `open`, error otherwise
+    fn consume_closing(&mut self, open: Token, open_span: Span) -> IntyResult<Token> {
+        let expected = open.closing_delimiter();
+
+        if self.peek() == expected.as_ref() {
+            self.advance();
+            return Ok(open);
+        }
+
+        Err(self.unclosed(open, open_span))
+    }
+
+    /// Build an error for a delimiter that was opened at `open_span` but never closed
+    fn unclosed(&self, open: Token, open_span: Span) -> IntyError {
+        let (open, expected) = match open {
+            Token::LeftParen => ('(', ')'),
+            Token::LeftBracket => ('[', ']'),
+            Token::LeftBrace => ('{', '}'),
+            _ => unreachable!("only opening delimiters can be unclosed"),
+        };
+
+        IntyError::UnclosedDelimiterError {
+            open,
+            expected,
+            open_span,
+            span: self.current_span(),
+        }
     }
 }
 
@@ -388,32 +1480,52 @@ impl<'a> Parser<'a> {
 mod tests {
     use super::*;
 
+    /// Attach empty spans to a list of tokens
+    fn spanned(tokens: &[Token]) -> Vec<SpannedToken> {
+        tokens
+            .iter()
+            .map(|token| SpannedToken::new(token.clone(), Span::default()))
+            .collect()
+    }
+
+    /// Build an expression without a location, since spans are ignored when comparing nodes
+    fn expr(kind: ExprKind) -> Expr {
+        Expr::new(kind, Span::default())
+    }
+
+    /// Build a statement without a location, since spans are ignored when comparing nodes
+    fn stmt(kind: StmtKind) -> Stmt {
+        Stmt::new(kind, Span::default())
+    }
+
     #[test]
     fn parsing_integer() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Integer(1))],
-            Parser::new(&vec![Token::Integer(1)]).parse().unwrap()
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Integer(1.into()))))],
+            Parser::new(&spanned(&[Token::Integer(1.into())]))
+                .parse()
+                .unwrap()
         );
     }
 
     #[test]
     fn parsing_unary_operators() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Unary {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Unary {
                 operator: UnOp::Plus,
-                value: Rc::new(Expr::Integer(1))
-            })],
-            Parser::new(&vec![Token::Plus, Token::Integer(1)])
+                value: Rc::new(expr(ExprKind::Integer(1.into())))
+            })))],
+            Parser::new(&spanned(&[Token::Plus, Token::Integer(1.into())]))
                 .parse()
                 .unwrap()
         );
 
         assert_eq!(
-            vec![Stmt::Expr(Expr::Unary {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Unary {
                 operator: UnOp::Minus,
-                value: Rc::new(Expr::Integer(1))
-            })],
-            Parser::new(&vec![Token::Hyphen, Token::Integer(1)])
+                value: Rc::new(expr(ExprKind::Integer(1.into())))
+            })))],
+            Parser::new(&spanned(&[Token::Hyphen, Token::Integer(1.into())]))
                 .parse()
                 .unwrap()
         );
@@ -422,36 +1534,40 @@ mod tests {
     #[test]
     fn parsing_binary_expression() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Binary {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Binary {
                 operator: BinOp::Add,
-                lhs: Rc::new(Expr::Integer(1)),
-                rhs: Rc::new(Expr::Integer(2))
-            })],
-            Parser::new(&vec![Token::Integer(1), Token::Plus, Token::Integer(2)])
-                .parse()
-                .unwrap()
+                lhs: Rc::new(expr(ExprKind::Integer(1.into()))),
+                rhs: Rc::new(expr(ExprKind::Integer(2.into())))
+            })))],
+            Parser::new(&spanned(&[
+                Token::Integer(1.into()),
+                Token::Plus,
+                Token::Integer(2.into())
+            ]))
+            .parse()
+            .unwrap()
         );
     }
 
     #[test]
     fn parsing_multiplication_expression() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Binary {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Binary {
                 operator: BinOp::Mul,
-                lhs: Rc::new(Expr::Binary {
+                lhs: Rc::new(expr(ExprKind::Binary {
                     operator: BinOp::Mul,
-                    lhs: Rc::new(Expr::Integer(2)),
-                    rhs: Rc::new(Expr::Integer(3)),
-                }),
-                rhs: Rc::new(Expr::Integer(4)),
-            })],
-            Parser::new(&vec![
-                Token::Integer(2),
+                    lhs: Rc::new(expr(ExprKind::Integer(2.into()))),
+                    rhs: Rc::new(expr(ExprKind::Integer(3.into()))),
+                })),
+                rhs: Rc::new(expr(ExprKind::Integer(4.into()))),
+            })))],
+            Parser::new(&spanned(&[
+                Token::Integer(2.into()),
                 Token::Star,
-                Token::Integer(3),
+                Token::Integer(3.into()),
                 Token::Star,
-                Token::Integer(4),
-            ])
+                Token::Integer(4.into()),
+            ]))
             .parse()
             .unwrap()
         );
@@ -460,28 +1576,28 @@ mod tests {
     #[test]
     fn parsing_exponentiation_expression() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Binary {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Binary {
                 operator: BinOp::Pow,
-                lhs: Rc::new(Expr::Integer(2)),
-                rhs: Rc::new(Expr::Binary {
+                lhs: Rc::new(expr(ExprKind::Integer(2.into()))),
+                rhs: Rc::new(expr(ExprKind::Binary {
                     operator: BinOp::Pow,
-                    lhs: Rc::new(Expr::Integer(3)),
-                    rhs: Rc::new(Expr::Binary {
+                    lhs: Rc::new(expr(ExprKind::Integer(3.into()))),
+                    rhs: Rc::new(expr(ExprKind::Binary {
                         operator: BinOp::Pow,
-                        lhs: Rc::new(Expr::Integer(4)),
-                        rhs: Rc::new(Expr::Integer(5)),
-                    }),
-                }),
-            })],
-            Parser::new(&vec![
-                Token::Integer(2),
+                        lhs: Rc::new(expr(ExprKind::Integer(4.into()))),
+                        rhs: Rc::new(expr(ExprKind::Integer(5.into()))),
+                    })),
+                })),
+            })))],
+            Parser::new(&spanned(&[
+                Token::Integer(2.into()),
                 Token::Caret,
-                Token::Integer(3),
+                Token::Integer(3.into()),
                 Token::Caret,
-                Token::Integer(4),
+                Token::Integer(4.into()),
                 Token::Caret,
-                Token::Integer(5),
-            ])
+                Token::Integer(5.into()),
+            ]))
             .parse()
             .unwrap()
         );
@@ -490,28 +1606,28 @@ mod tests {
     #[test]
     fn parsing_complex_expression() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Binary {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Binary {
                 operator: BinOp::Add,
-                lhs: Rc::new(Expr::Integer(1)),
-                rhs: Rc::new(Expr::Binary {
+                lhs: Rc::new(expr(ExprKind::Integer(1.into()))),
+                rhs: Rc::new(expr(ExprKind::Binary {
                     operator: BinOp::Mul,
-                    lhs: Rc::new(Expr::Integer(2)),
-                    rhs: Rc::new(Expr::Binary {
+                    lhs: Rc::new(expr(ExprKind::Integer(2.into()))),
+                    rhs: Rc::new(expr(ExprKind::Binary {
                         operator: BinOp::Pow,
-                        lhs: Rc::new(Expr::Integer(3)),
-                        rhs: Rc::new(Expr::Integer(4)),
-                    }),
-                }),
-            })],
-            Parser::new(&vec![
-                Token::Integer(1),
+                        lhs: Rc::new(expr(ExprKind::Integer(3.into()))),
+                        rhs: Rc::new(expr(ExprKind::Integer(4.into()))),
+                    })),
+                })),
+            })))],
+            Parser::new(&spanned(&[
+                Token::Integer(1.into()),
                 Token::Plus,
-                Token::Integer(2),
+                Token::Integer(2.into()),
                 Token::Star,
-                Token::Integer(3),
+                Token::Integer(3.into()),
                 Token::Caret,
-                Token::Integer(4),
-            ])
+                Token::Integer(4.into()),
+            ]))
             .parse()
             .unwrap()
         );
@@ -520,24 +1636,24 @@ mod tests {
     #[test]
     fn parsing_expression_with_parentheses() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Binary {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Binary {
                 operator: BinOp::Mul,
-                lhs: Rc::new(Expr::Binary {
+                lhs: Rc::new(expr(ExprKind::Binary {
                     operator: BinOp::Add,
-                    lhs: Rc::new(Expr::Integer(1)),
-                    rhs: Rc::new(Expr::Integer(2)),
-                }),
-                rhs: Rc::new(Expr::Integer(3)),
-            })],
-            Parser::new(&vec![
+                    lhs: Rc::new(expr(ExprKind::Integer(1.into()))),
+                    rhs: Rc::new(expr(ExprKind::Integer(2.into()))),
+                })),
+                rhs: Rc::new(expr(ExprKind::Integer(3.into()))),
+            })))],
+            Parser::new(&spanned(&[
                 Token::LeftParen,
-                Token::Integer(1),
+                Token::Integer(1.into()),
                 Token::Plus,
-                Token::Integer(2),
+                Token::Integer(2.into()),
                 Token::RightParen,
                 Token::Star,
-                Token::Integer(3),
-            ])
+                Token::Integer(3.into()),
+            ]))
             .parse()
             .unwrap()
         );
@@ -556,7 +1672,7 @@ mod tests {
         ]
         .into_iter()
         .for_each(|token| {
-            let ast = Parser::new(&vec![token.clone()]).parse();
+            let ast = Parser::new(&spanned(&[token])).parse();
 
             assert!(ast.is_err());
         })
@@ -565,12 +1681,12 @@ mod tests {
     #[test]
     fn parsing_logical_and_expression() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Logical {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Logical {
                 operator: LogOp::And,
-                lhs: Rc::new(Expr::Bool(true)),
-                rhs: Rc::new(Expr::Bool(false))
-            })],
-            Parser::new(&vec![Token::True, Token::And, Token::False,])
+                lhs: Rc::new(expr(ExprKind::Bool(true))),
+                rhs: Rc::new(expr(ExprKind::Bool(false)))
+            })))],
+            Parser::new(&spanned(&[Token::True, Token::And, Token::False,]))
                 .parse()
                 .unwrap()
         );
@@ -579,12 +1695,12 @@ mod tests {
     #[test]
     fn parsing_logical_or_expression() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Logical {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Logical {
                 operator: LogOp::Or,
-                lhs: Rc::new(Expr::Bool(true)),
-                rhs: Rc::new(Expr::Bool(false))
-            })],
-            Parser::new(&vec![Token::True, Token::Or, Token::False,])
+                lhs: Rc::new(expr(ExprKind::Bool(true))),
+                rhs: Rc::new(expr(ExprKind::Bool(false)))
+            })))],
+            Parser::new(&spanned(&[Token::True, Token::Or, Token::False,]))
                 .parse()
                 .unwrap()
         );
@@ -593,20 +1709,20 @@ mod tests {
     #[test]
     fn parsing_logical_and_or_expression() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Logical {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Logical {
                 operator: LogOp::Or,
-                lhs: Rc::new(Expr::Logical {
+                lhs: Rc::new(expr(ExprKind::Logical {
                     operator: LogOp::And,
-                    lhs: Rc::new(Expr::Bool(true)),
-                    rhs: Rc::new(Expr::Bool(false)),
-                }),
-                rhs: Rc::new(Expr::Logical {
+                    lhs: Rc::new(expr(ExprKind::Bool(true))),
+                    rhs: Rc::new(expr(ExprKind::Bool(false))),
+                })),
+                rhs: Rc::new(expr(ExprKind::Logical {
                     operator: LogOp::And,
-                    lhs: Rc::new(Expr::Bool(false)),
-                    rhs: Rc::new(Expr::Bool(true)),
-                }),
-            })],
-            Parser::new(&vec![
+                    lhs: Rc::new(expr(ExprKind::Bool(false))),
+                    rhs: Rc::new(expr(ExprKind::Bool(true))),
+                })),
+            })))],
+            Parser::new(&spanned(&[
                 Token::True,
                 Token::And,
                 Token::False,
@@ -614,7 +1730,7 @@ mod tests {
                 Token::False,
                 Token::And,
                 Token::True,
-            ])
+            ]))
             .parse()
             .unwrap()
         );
@@ -623,20 +1739,20 @@ mod tests {
     #[test]
     fn parsing_logical_or_and_expression() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::Logical {
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Logical {
                 operator: LogOp::Or,
-                lhs: Rc::new(Expr::Logical {
+                lhs: Rc::new(expr(ExprKind::Logical {
                     operator: LogOp::Or,
-                    lhs: Rc::new(Expr::Bool(true)),
-                    rhs: Rc::new(Expr::Logical {
+                    lhs: Rc::new(expr(ExprKind::Bool(true))),
+                    rhs: Rc::new(expr(ExprKind::Logical {
                         operator: LogOp::And,
-                        lhs: Rc::new(Expr::Bool(false)),
-                        rhs: Rc::new(Expr::Bool(false)),
-                    }),
-                }),
-                rhs: Rc::new(Expr::Bool(true)),
-            })],
-            Parser::new(&vec![
+                        lhs: Rc::new(expr(ExprKind::Bool(false))),
+                        rhs: Rc::new(expr(ExprKind::Bool(false))),
+                    })),
+                })),
+                rhs: Rc::new(expr(ExprKind::Bool(true))),
+            })))],
+            Parser::new(&spanned(&[
                 Token::True,
                 Token::Or,
                 Token::False,
@@ -644,7 +1760,7 @@ mod tests {
                 Token::False,
                 Token::Or,
                 Token::True,
-            ])
+            ]))
             .parse()
             .unwrap()
         );
@@ -653,20 +1769,419 @@ mod tests {
     #[test]
     fn parsing_arrays() {
         assert_eq!(
-            vec![Stmt::Expr(Expr::List(vec![
-                Expr::Integer(1),
-                Expr::Integer(2),
-                Expr::Integer(3)
-            ]))],
-            Parser::new(&vec![
+            vec![stmt(StmtKind::Expr(expr(ExprKind::List(vec![
+                expr(ExprKind::Integer(1.into())),
+                expr(ExprKind::Integer(2.into())),
+                expr(ExprKind::Integer(3.into()))
+            ]))))],
+            Parser::new(&spanned(&[
+                Token::LeftBracket,
+                Token::Integer(1.into()),
+                Token::Comma,
+                Token::Integer(2.into()),
+                Token::Comma,
+                Token::Integer(3.into()),
+                Token::RightBracket,
+            ]))
+            .parse()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parsing_spread() {
+        assert_eq!(
+            vec![stmt(StmtKind::Expr(expr(ExprKind::List(vec![
+                expr(ExprKind::Integer(0.into())),
+                expr(ExprKind::Spread(Rc::new(expr(ExprKind::Ident(
+                    "xs".to_string()
+                )))))
+            ]))))],
+            Parser::new(&spanned(&[
+                Token::LeftBracket,
+                Token::Integer(0.into()),
+                Token::Comma,
+                Token::DotDotDot,
+                Token::Ident("xs".to_string()),
+                Token::RightBracket,
+            ]))
+            .parse()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parsing_update_operators() {
+        assert_eq!(
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Unary {
+                operator: UnOp::Minus,
+                value: Rc::new(expr(ExprKind::Update {
+                    operator: UpdOp::Inc,
+                    ident: "x".to_string(),
+                    prefix: false,
+                })),
+            })))],
+            Parser::new(&spanned(&[
+                Token::Hyphen,
+                Token::Ident("x".to_string()),
+                Token::PlusPlus
+            ]))
+            .parse()
+            .unwrap()
+        );
+
+        assert_eq!(
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Update {
+                operator: UpdOp::Dec,
+                ident: "x".to_string(),
+                prefix: true,
+            })))],
+            Parser::new(&spanned(&[
+                Token::HyphenHyphen,
+                Token::Ident("x".to_string())
+            ]))
+            .parse()
+            .unwrap()
+        );
+
+        assert!(
+            Parser::new(&spanned(&[Token::Integer(1.into()), Token::PlusPlus]))
+                .parse()
+                .is_err()
+        );
+        assert!(
+            Parser::new(&spanned(&[Token::PlusPlus, Token::Integer(1.into())]))
+                .parse()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parsing_function_call() {
+        assert_eq!(
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Call {
+                callee: Rc::new(expr(ExprKind::Ident("foo".to_string()))),
+                args: vec![
+                    expr(ExprKind::Integer(1.into())),
+                    expr(ExprKind::Binary {
+                        operator: BinOp::Add,
+                        lhs: Rc::new(expr(ExprKind::Integer(2.into()))),
+                        rhs: Rc::new(expr(ExprKind::Integer(3.into()))),
+                    }),
+                ],
+            })))],
+            Parser::new(&spanned(&[
+                Token::Ident("foo".to_string()),
+                Token::LeftParen,
+                Token::Integer(1.into()),
+                Token::Comma,
+                Token::Integer(2.into()),
+                Token::Plus,
+                Token::Integer(3.into()),
+                Token::RightParen,
+            ]))
+            .parse()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parsing_index_and_slice() {
+        assert_eq!(
+            vec![stmt(StmtKind::Expr(expr(ExprKind::Slice {
+                target: Rc::new(expr(ExprKind::Index {
+                    target: Rc::new(expr(ExprKind::Ident("xs".to_string()))),
+                    index: Rc::new(expr(ExprKind::Unary {
+                        operator: UnOp::Minus,
+                        value: Rc::new(expr(ExprKind::Integer(1.into()))),
+                    })),
+                })),
+                start: None,
+                end: Some(Rc::new(expr(ExprKind::Integer(2.into())))),
+            })))],
+            Parser::new(&spanned(&[
+                Token::Ident("xs".to_string()),
+                Token::LeftBracket,
+                Token::Hyphen,
+                Token::Integer(1.into()),
+                Token::RightBracket,
+                Token::LeftBracket,
+                Token::Colon,
+                Token::Integer(2.into()),
+                Token::RightBracket,
+            ]))
+            .parse()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parsing_map_and_block() {
+        assert_eq!(
+            vec![
+                stmt(StmtKind::Expr(expr(ExprKind::Map(vec![
+                    (
+                        expr(ExprKind::Str("a".to_string())),
+                        expr(ExprKind::Integer(1.into())),
+                    ),
+                    (
+                        expr(ExprKind::Ident("b".to_string())),
+                        expr(ExprKind::Block(vec![])),
+                    ),
+                ])))),
+                stmt(StmtKind::Expr(expr(ExprKind::Map(vec![])))),
+                stmt(StmtKind::Expr(expr(ExprKind::Block(vec![stmt(
+                    StmtKind::Expr(expr(ExprKind::Str("a".to_string())),)
+                )])))),
+            ],
+            Parser::new(&spanned(&[
+                Token::LeftBrace,
+                Token::Str("a".to_string()),
+                Token::Colon,
+                Token::Integer(1.into()),
+                Token::Comma,
+                Token::Ident("b".to_string()),
+                Token::Colon,
+                Token::LeftBrace,
+                Token::RightBrace,
+                Token::Comma,
+                Token::RightBrace,
+                Token::Semicolon,
+                Token::LeftBrace,
+                Token::Colon,
+                Token::RightBrace,
+                Token::Semicolon,
+                Token::LeftBrace,
+                Token::Str("a".to_string()),
+                Token::RightBrace,
+            ]))
+            .parse()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parsing_index_assignment() {
+        assert_eq!(
+            vec![
+                stmt(StmtKind::SetIndex {
+                    ident: "m".to_string(),
+                    path: vec![
+                        Rc::new(expr(ExprKind::Integer(1.into()))),
+                        Rc::new(expr(ExprKind::Integer(2.into()))),
+                    ],
+                    expr: expr(ExprKind::Integer(3.into())),
+                }),
+                stmt(StmtKind::Delete {
+                    ident: "m".to_string(),
+                    path: vec![Rc::new(expr(ExprKind::Integer(1.into())))],
+                }),
+            ],
+            Parser::new(&spanned(&[
+                Token::Ident("m".to_string()),
+                Token::LeftBracket,
+                Token::Integer(1.into()),
+                Token::RightBracket,
+                Token::LeftBracket,
+                Token::Integer(2.into()),
+                Token::RightBracket,
+                Token::Equal,
+                Token::Integer(3.into()),
+                Token::Semicolon,
+                Token::Delete,
+                Token::Ident("m".to_string()),
                 Token::LeftBracket,
-                Token::Integer(1),
+                Token::Integer(1.into()),
+                Token::RightBracket,
+            ]))
+            .parse()
+            .unwrap()
+        );
+
+        // Only indexed variables can be assigned to
+        assert!(matches!(
+            Parser::new(&spanned(&[
+                Token::Integer(1.into()),
+                Token::Equal,
+                Token::Integer(2.into()),
+            ]))
+            .parse(),
+            Err(IntyError::SyntaxError { .. })
+        ));
+    }
+
+    #[test]
+    fn parsing_tuple_and_let_pattern() {
+        assert_eq!(
+            vec![stmt(StmtKind::Let {
+                pattern: Pattern::new(
+                    PatternKind::Tuple(vec![
+                        Pattern::ident("a".to_string(), Span::default()),
+                        Pattern::new(
+                            PatternKind::List {
+                                items: vec![Pattern::new(PatternKind::Wildcard, Span::default())],
+                                rest: Some(Rc::new(Pattern::ident(
+                                    "rest".to_string(),
+                                    Span::default()
+                                ))),
+                            },
+                            Span::default()
+                        ),
+                    ]),
+                    Span::default()
+                ),
+                expr: expr(ExprKind::Tuple(vec![
+                    expr(ExprKind::Integer(1.into())),
+                    expr(ExprKind::Integer(2.into())),
+                ])),
+            })],
+            Parser::new(&spanned(&[
+                Token::Let,
+                Token::LeftParen,
+                Token::Ident("a".to_string()),
                 Token::Comma,
-                Token::Integer(2),
+                Token::LeftBracket,
+                Token::Ident("_".to_string()),
                 Token::Comma,
-                Token::Integer(3),
+                Token::DotDotDot,
+                Token::Ident("rest".to_string()),
                 Token::RightBracket,
-            ])
+                Token::RightParen,
+                Token::Equal,
+                Token::LeftParen,
+                Token::Integer(1.into()),
+                Token::Comma,
+                Token::Integer(2.into()),
+                Token::Comma,
+                Token::RightParen,
+            ]))
+            .parse()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parsing_struct_and_record() {
+        assert_eq!(
+            vec![
+                stmt(StmtKind::Struct {
+                    ident: "Point".to_string(),
+                    fields: vec!["x".to_string(), "y".to_string()],
+                }),
+                stmt(StmtKind::Expr(expr(ExprKind::Field {
+                    target: Rc::new(expr(ExprKind::Record {
+                        ident: "Point".to_string(),
+                        fields: vec![
+                            ("x".to_string(), expr(ExprKind::Integer(1.into()))),
+                            ("y".to_string(), expr(ExprKind::Ident("y".to_string()))),
+                        ],
+                        base: Some(Rc::new(expr(ExprKind::Ident("p".to_string())))),
+                    })),
+                    field: "x".to_string(),
+                }))),
+            ],
+            Parser::new(&spanned(&[
+                Token::Struct,
+                Token::Ident("Point".to_string()),
+                Token::LeftBrace,
+                Token::Ident("x".to_string()),
+                Token::Comma,
+                Token::Ident("y".to_string()),
+                Token::RightBrace,
+                Token::Semicolon,
+                Token::Ident("Point".to_string()),
+                Token::LeftBrace,
+                Token::Ident("x".to_string()),
+                Token::Colon,
+                Token::Integer(1.into()),
+                Token::Comma,
+                Token::Ident("y".to_string()),
+                Token::Comma,
+                Token::DotDot,
+                Token::Ident("p".to_string()),
+                Token::RightBrace,
+                Token::Dot,
+                Token::Ident("x".to_string()),
+            ]))
+            .parse()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parsing_enum_and_match() {
+        let pattern = |kind| Pattern::new(kind, Span::default());
+        let circle = || {
+            pattern(PatternKind::Variant {
+                ident: "Circle".to_string(),
+                fields: vec![Pattern::ident("r".to_string(), Span::default())],
+            })
+        };
+
+        assert_eq!(
+            vec![
+                stmt(StmtKind::Enum {
+                    ident: "Shape".to_string(),
+                    variants: vec![
+                        ("Circle".to_string(), vec!["r".to_string()]),
+                        ("Empty".to_string(), vec![]),
+                    ],
+                }),
+                stmt(StmtKind::Expr(expr(ExprKind::Match {
+                    value: Rc::new(expr(ExprKind::Ident("Empty".to_string()))),
+                    arms: vec![
+                        MatchArm {
+                            pattern: circle(),
+                            guard: Some(expr(ExprKind::Ident("r".to_string()))),
+                            body: stmt(StmtKind::Expr(expr(ExprKind::Ident("r".to_string())))),
+                        },
+                        MatchArm {
+                            pattern: pattern(PatternKind::Literal(Value::Integer((-1).into()))),
+                            guard: None,
+                            body: stmt(StmtKind::Expr(expr(ExprKind::Block(vec![])))),
+                        },
+                        MatchArm {
+                            pattern: pattern(PatternKind::Wildcard),
+                            guard: None,
+                            body: stmt(StmtKind::Expr(expr(ExprKind::Integer(0.into())))),
+                        },
+                    ],
+                }))),
+            ],
+            Parser::new(&spanned(&[
+                Token::Enum,
+                Token::Ident("Shape".to_string()),
+                Token::LeftBrace,
+                Token::Ident("Circle".to_string()),
+                Token::LeftParen,
+                Token::Ident("r".to_string()),
+                Token::RightParen,
+                Token::Comma,
+                Token::Ident("Empty".to_string()),
+                Token::RightBrace,
+                Token::Semicolon,
+                Token::Match,
+                Token::Ident("Empty".to_string()),
+                Token::LeftBrace,
+                Token::Ident("Circle".to_string()),
+                Token::LeftParen,
+                Token::Ident("r".to_string()),
+                Token::RightParen,
+                Token::If,
+                Token::Ident("r".to_string()),
+                Token::FatArrow,
+                Token::Ident("r".to_string()),
+                Token::Comma,
+                Token::Hyphen,
+                Token::Integer(1.into()),
+                Token::FatArrow,
+                Token::LeftBrace,
+                Token::RightBrace,
+                Token::Ident("_".to_string()),
+                Token::FatArrow,
+                Token::Integer(0.into()),
+                Token::RightBrace,
+            ]))
             .parse()
             .unwrap()
         );